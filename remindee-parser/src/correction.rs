@@ -0,0 +1,209 @@
+use std::ops::Range;
+
+use crate::grammar::parse_reminder_in;
+use crate::keywords::Language;
+
+/// English keywords to correct the typos into, the shorter ones
+/// are left out as too many ordinary words are a typo away from them
+const KEYWORDS: &[&str] = &[
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+    "january",
+    "february",
+    "march",
+    "april",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+    "today",
+    "tomorrow",
+    "noon",
+    "midnight",
+    "every",
+    "each",
+    "next",
+    "after",
+    "before",
+    "except",
+    "between",
+    "random",
+    "randomly",
+    "once",
+    "twice",
+    "times",
+    "first",
+    "second",
+    "third",
+    "fourth",
+    "fifth",
+    "last",
+    "other",
+    "half",
+    "quarter",
+    "year",
+    "years",
+    "month",
+    "months",
+    "week",
+    "weeks",
+    "days",
+    "hour",
+    "hours",
+    "minute",
+    "minutes",
+    "seconds",
+    "workday",
+    "workdays",
+    "weekday",
+    "weekdays",
+    "weekend",
+    "weekends",
+    "business",
+    "sunrise",
+    "sunset",
+    "summer",
+    "autumn",
+    "winter",
+    "spring",
+];
+
+/// Optimal string alignment distance, a transposition of
+/// two adjacent letters counts as a single edit
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    dist[a.len()][b.len()]
+}
+
+/// The nearest keyword if the word is a typo away from it
+fn nearest_keyword<'a>(word: &str, keywords: &[&'a str]) -> Option<&'a str> {
+    let word: Vec<char> = word.to_lowercase().chars().collect();
+    if word.len() < 4 {
+        return None;
+    }
+    let max_distance = if word.len() < 7 { 1 } else { 2 };
+    let mut nearest = None;
+    for &keyword in keywords {
+        let keyword_chars: Vec<char> = keyword.chars().collect();
+        let distance = edit_distance(&word, &keyword_chars);
+        if distance == 0 {
+            return None;
+        }
+        // prefer the keywords of the same length on a tie, e.g. hours for hourz
+        let key = (distance, word.len().abs_diff(keyword_chars.len()));
+        if distance <= max_distance
+            && nearest.is_none_or(|(_, nearest)| key < nearest)
+        {
+            nearest = Some((keyword, key));
+        }
+    }
+    nearest.map(|(keyword, _)| keyword)
+}
+
+/// The English keyword the word is a typo away from
+pub(crate) fn misspelled_keyword(word: &str) -> Option<&'static str> {
+    nearest_keyword(word, KEYWORDS)
+}
+
+fn words(s: &str) -> Vec<Range<usize>> {
+    let mut words = vec![];
+    let mut start = None;
+    for (i, c) in s.char_indices() {
+        match (c.is_alphabetic(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(from)) => {
+                words.push(from..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        words.push(from..s.len());
+    }
+    words
+}
+
+/// Length of the description left after parsing the text,
+/// or `None` if it isn't a reminder
+fn description_len(s: &str, language: Language) -> Option<usize> {
+    let reminder = parse_reminder_in(s, language).ok()?;
+    reminder.pattern.as_ref()?;
+    Some(
+        reminder
+            .description
+            .map_or(0, |description| description.0.chars().count()),
+    )
+}
+
+/// Correct the words that look like misspelled keywords, keeping only
+/// the corrections that make more of the text a part of the pattern
+pub fn suggest_correction(s: &str, language: Language) -> Option<String> {
+    let keywords: Vec<&str> = KEYWORDS
+        .iter()
+        .copied()
+        .chain(
+            language
+                .keywords()
+                .iter()
+                .map(|&(keyword, _)| keyword)
+                .filter(|keyword| !keyword.contains(' ')),
+        )
+        .collect();
+    let mut corrected = s.to_owned();
+    let mut best = description_len(s, language).unwrap_or(usize::MAX);
+    // going from the end keeps the ranges of the preceding words valid
+    for range in words(s).into_iter().rev() {
+        let word = &s[range.clone()];
+        let Some(keyword) = nearest_keyword(word, &keywords) else {
+            continue;
+        };
+        let mut replacement = keyword.to_owned();
+        if word.starts_with(char::is_uppercase) {
+            replacement = replacement
+                .chars()
+                .take(1)
+                .flat_map(char::to_uppercase)
+                .chain(replacement.chars().skip(1))
+                .collect();
+        }
+        let candidate = format!(
+            "{}{}{}",
+            &corrected[..range.start],
+            replacement,
+            &corrected[range.end..]
+        );
+        if let Some(len) = description_len(&candidate, language) {
+            if len < best {
+                corrected = candidate;
+                best = len;
+            }
+        }
+    }
+    (corrected != s).then_some(corrected)
+}
@@ -0,0 +1,183 @@
+use std::ops::Range;
+
+use pest::error::{ErrorVariant, InputLocation};
+use pest::Span;
+
+use crate::grammar::Rule;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Byte range of the offending part of the input
+    pub span: Range<usize>,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    /// The input doesn't match the grammar
+    Syntax {
+        expected: Vec<Expected>,
+    },
+    /// A date that doesn't exist, e.g. 31.02
+    InvalidDate,
+    ZeroInterval,
+    /// A number that doesn't fit into its field
+    InvalidNumber,
+    /// A number of times that is zero, e.g. x0
+    ZeroCount,
+    /// A fraction of a unit that can't be split into smaller ones,
+    /// e.g. 1.5mo
+    FractionNotAllowed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Expected {
+    Date,
+    Time,
+    Weekday,
+    Interval,
+}
+
+impl Expected {
+    fn from_rule(rule: Rule) -> &'static [Self] {
+        match rule {
+            Rule::reminder => &[Self::Date, Self::Time, Self::Interval],
+            Rule::day
+            | Rule::month
+            | Rule::year
+            | Rule::january
+            | Rule::february
+            | Rule::march
+            | Rule::april
+            | Rule::may
+            | Rule::june
+            | Rule::july
+            | Rule::august
+            | Rule::september
+            | Rule::october
+            | Rule::november
+            | Rule::december
+            | Rule::today
+            | Rule::tomorrow
+            | Rule::day_after_tomorrow
+            | Rule::date_from
+            | Rule::date_until
+            | Rule::dates_point
+            | Rule::dates_range
+            | Rule::dates_relative
+            | Rule::except_date
+            | Rule::except_dates
+            | Rule::month_end => &[Self::Date],
+            Rule::hour
+            | Rule::hour12
+            | Rule::minute
+            | Rule::second
+            | Rule::am
+            | Rule::pm
+            | Rule::noon
+            | Rule::midnight
+            | Rule::time_from
+            | Rule::time_until
+            | Rule::time_point
+            | Rule::time_range
+            | Rule::random_times
+            | Rule::time_sun
+            | Rule::sunrise
+            | Rule::sunset
+            | Rule::except_time => &[Self::Time],
+            Rule::monday
+            | Rule::tuesday
+            | Rule::wednesday
+            | Rule::thursday
+            | Rule::friday
+            | Rule::saturday
+            | Rule::sunday
+            | Rule::weekday_from
+            | Rule::weekday_to
+            | Rule::weekdays_range
+            | Rule::workdays
+            | Rule::weekends
+            | Rule::filter_weekdays
+            | Rule::ordinal_weekday => &[Self::Weekday],
+            Rule::interval
+            | Rule::time_interval
+            | Rule::date_interval
+            | Rule::every_other
+            | Rule::interval_years
+            | Rule::interval_months
+            | Rule::interval_weeks
+            | Rule::interval_days
+            | Rule::interval_hours
+            | Rule::interval_minutes
+            | Rule::interval_seconds
+            | Rule::interval_half_hour
+            | Rule::interval_quarter_hour
+            | Rule::countdown
+            | Rule::recurrence_offset
+            | Rule::limit_duration => &[Self::Interval],
+            _ => &[],
+        }
+    }
+}
+
+impl ParseError {
+    pub(crate) fn new(span: Span<'_>, kind: ParseErrorKind) -> Self {
+        Self {
+            span: span.start()..span.end(),
+            kind,
+        }
+    }
+}
+
+impl From<pest::error::Error<Rule>> for ParseError {
+    fn from(err: pest::error::Error<Rule>) -> Self {
+        let span = match err.location {
+            InputLocation::Pos(pos) => pos..pos,
+            InputLocation::Span((start, end)) => start..end,
+        };
+        let mut expected = match err.variant {
+            ErrorVariant::ParsingError { positives, .. } => positives
+                .into_iter()
+                .flat_map(|rule| Expected::from_rule(rule).iter().copied())
+                .collect(),
+            ErrorVariant::CustomError { .. } => vec![],
+        };
+        expected.sort();
+        expected.dedup();
+        Self {
+            span,
+            kind: ParseErrorKind::Syntax { expected },
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            ParseErrorKind::Syntax { ref expected } => {
+                write!(f, "unexpected input at {}", self.span.start)?;
+                if !expected.is_empty() {
+                    write!(f, ", expected {expected:?}")?;
+                }
+                Ok(())
+            }
+            ParseErrorKind::InvalidDate => {
+                write!(f, "invalid date at {:?}", self.span)
+            }
+            ParseErrorKind::ZeroInterval => {
+                write!(f, "zero interval at {:?}", self.span)
+            }
+            ParseErrorKind::InvalidNumber => {
+                write!(f, "invalid number at {:?}", self.span)
+            }
+            ParseErrorKind::ZeroCount => {
+                write!(f, "zero count at {:?}", self.span)
+            }
+            ParseErrorKind::FractionNotAllowed => {
+                write!(f, "fraction not allowed at {:?}", self.span)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,9 +1,16 @@
 use bitmask_enum::bitmask;
 use nonempty::{nonempty, NonEmpty};
 
-use pest::{iterators::Pair, Parser};
+use pest::{iterators::Pair, Parser, Span};
 use pest_derive::Parser;
 
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::correction::misspelled_keyword;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::keywords::{Language, Translation};
+
 extern crate alloc;
 
 #[derive(Parser)]
@@ -50,10 +57,22 @@ pub enum Weekdays {
     Sunday,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct OrdinalWeekday {
+    /// 1 to 5 for the n-th weekday of a month, -1 for the last one
+    pub ordinal: i32,
+    pub weekday: Weekdays,
+}
+
 #[derive(Debug)]
 pub enum DateDivisor {
     Weekdays(Weekdays),
     Interval(DateInterval),
+    OrdinalWeekday(OrdinalWeekday),
+    /// Number of days before the last day of a month
+    MonthEnd(u32),
+    /// Monday to Friday, except public holidays
+    Workdays,
 }
 
 #[derive(Debug)]
@@ -76,9 +95,21 @@ impl Default for DateRange {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum RelativeDate {
+    Today,
+    Tomorrow,
+    DayAfterTomorrow,
+    /// The nearest given weekday, today included
+    ThisWeekday(Weekdays),
+    /// The nearest given weekday after today
+    NextWeekday(Weekdays),
+}
+
 #[derive(Debug)]
 pub enum DatePattern {
     Point(HoleyDate),
+    Relative(RelativeDate),
     Range(DateRange),
 }
 
@@ -89,14 +120,14 @@ pub struct Time {
     pub second: u32,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct TimeInterval {
     pub hours: u32,
     pub minutes: u32,
     pub seconds: u32,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct DateInterval {
     pub years: i32,
     pub months: u32,
@@ -109,18 +140,84 @@ pub struct TimeRange {
     pub from: Option<Time>,
     pub until: Option<Time>,
     pub interval: TimeInterval,
+    /// Number of random times in the range per date, used instead of
+    /// the interval
+    pub random: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SunEvent {
+    Sunrise,
+    Sunset,
+}
+
+/// Time of a sun event on each date, shifted by the offset
+#[derive(Debug, Clone, Copy)]
+pub struct SunTime {
+    pub event: SunEvent,
+    pub offset: TimeInterval,
+    /// Whether the offset goes before the event
+    pub before: bool,
 }
 
 #[derive(Debug)]
 pub enum TimePattern {
     Point(Time),
     Range(TimeRange),
+    Sun(SunTime),
+}
+
+/// Dates with their own times
+#[derive(Debug)]
+pub struct RecurrenceGroup {
+    pub dates_patterns: NonEmpty<DatePattern>,
+    pub time_patterns: Vec<TimePattern>,
 }
 
 #[derive(Debug)]
 pub struct Recurrence {
+    /// Delay after which the dates start
+    pub offset: Option<DateInterval>,
     pub dates_patterns: NonEmpty<DatePattern>,
     pub time_patterns: Vec<TimePattern>,
+    pub timezone: Option<TimeZone>,
+    pub exceptions: Vec<Exception>,
+    pub filters: Vec<DateFilter>,
+    pub limit: Option<RecurrenceLimit>,
+    /// Groups of dates with their own times besides the first one
+    pub groups: Vec<RecurrenceGroup>,
+}
+
+#[derive(Debug)]
+pub enum RecurrenceLimit {
+    /// Total number of occurrences
+    Count(u32),
+    /// Time after which there are no more occurrences
+    Duration(Interval),
+}
+
+/// Constraint that all the dates of a recurrence must satisfy
+#[derive(Debug)]
+pub enum DateFilter {
+    /// Months from 1 to 12
+    Months(Vec<u32>),
+    Weekdays(Weekdays),
+}
+
+#[derive(Debug)]
+pub enum Exception {
+    Weekdays(Weekdays),
+    Date(HoleyDate),
+    DateRange(HoleyDate, HoleyDate),
+    Time(Time),
+}
+
+#[derive(Debug)]
+pub enum TimeZone {
+    /// IANA timezone name, e.g. Europe/Berlin
+    Name(String),
+    /// Fixed offset east of UTC in minutes
+    Offset(i32),
 }
 
 #[derive(Debug, Default)]
@@ -129,6 +226,7 @@ pub struct Countdown {
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum ReminderPattern {
     Recurrence(Recurrence),
     Countdown(Countdown),
@@ -144,62 +242,307 @@ pub struct Reminder {
 pub struct Description(pub String);
 
 trait Parse {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self>
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError>
     where
         Self: Sized;
 }
 
+/// Value of a number spelled out in English, e.g. "twenty-five"
+fn spelled_number(s: &str) -> Option<u32> {
+    const ONES: [&str; 19] = [
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 5] = ["twenty", "thirty", "forty", "fifty", "sixty"];
+    s.split(['-', ' '])
+        .filter(|word| !word.is_empty())
+        .map(|word| match word.to_lowercase().as_str() {
+            "a" | "an" => Some(1),
+            "other" => Some(2),
+            word => ONES
+                .iter()
+                .position(|&one| one == word)
+                .map(|i| i as u32 + 1)
+                .or_else(|| {
+                    TENS.iter()
+                        .position(|&ten| ten == word)
+                        .map(|i| (i as u32 + 2) * 10)
+                }),
+        })
+        .sum()
+}
+
+fn parse_number<T: FromStr>(pair: &Pair<'_, Rule>) -> Result<T, ParseError> {
+    let s = pair.as_str();
+    let spelled = spelled_number(s).map(|number| number.to_string());
+    spelled.as_deref().unwrap_or(s).parse().map_err(|_| {
+        ParseError::new(pair.as_span(), ParseErrorKind::InvalidNumber)
+    })
+}
+
+fn days_in_month(year: Option<i32>, month: u32) -> u32 {
+    match month {
+        2 => match year {
+            Some(year)
+                if year % 4 != 0 || year % 100 == 0 && year % 400 != 0 =>
+            {
+                28
+            }
+            _ => 29,
+        },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn month_from_rule(rule: Rule) -> Option<u32> {
+    match rule {
+        Rule::january => Some(1),
+        Rule::february => Some(2),
+        Rule::march => Some(3),
+        Rule::april => Some(4),
+        Rule::may => Some(5),
+        Rule::june => Some(6),
+        Rule::july => Some(7),
+        Rule::august => Some(8),
+        Rule::september => Some(9),
+        Rule::october => Some(10),
+        Rule::november => Some(11),
+        Rule::december => Some(12),
+        _ => None,
+    }
+}
+
+/// Name of the IANA timezone matching the given one regardless of case
+fn known_timezone(name: &str) -> Option<&'static str> {
+    chrono_tz::TZ_VARIANTS
+        .iter()
+        .map(|tz| tz.name())
+        .find(|tz| tz.eq_ignore_ascii_case(name))
+}
+
+/// The zone most people mean by the abbreviation
+fn abbreviation_timezone(abbreviation: &str) -> &'static str {
+    match abbreviation {
+        "UTC" | "GMT" => "UTC",
+        "BST" => "Europe/London",
+        "CET" | "CEST" => "Europe/Berlin",
+        "EET" | "EEST" => "Europe/Helsinki",
+        "MSK" => "Europe/Moscow",
+        "IST" => "Asia/Kolkata",
+        "JST" => "Asia/Tokyo",
+        "AEST" | "AEDT" => "Australia/Sydney",
+        "EST" | "EDT" => "America/New_York",
+        "CST" | "CDT" => "America/Chicago",
+        "MST" | "MDT" => "America/Denver",
+        "PST" | "PDT" => "America/Los_Angeles",
+        _ => unreachable!(),
+    }
+}
+
 impl Parse for HoleyDate {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let span = pair.as_span();
         let mut holey_date = Self::default();
         for rec in pair.into_inner() {
             match rec.as_rule() {
                 Rule::year => {
-                    holey_date.year = Some(rec.as_str().parse().ok()?);
+                    holey_date.year = Some(parse_number(&rec)?);
                 }
                 Rule::month => {
-                    holey_date.month = Some(rec.as_str().parse().ok()?);
+                    holey_date.month = Some(parse_number(&rec)?);
                 }
                 Rule::day => {
-                    holey_date.day = Some(rec.as_str().parse().ok()?);
+                    holey_date.day = Some(parse_number(&rec)?);
+                }
+                rule => {
+                    holey_date.month = Some(month_from_rule(rule).unwrap());
                 }
-                _ => unreachable!(),
             }
         }
-        Some(holey_date)
+        if let (Some(month), Some(day)) = (holey_date.month, holey_date.day) {
+            if day > days_in_month(holey_date.year, month) {
+                return Err(ParseError::new(span, ParseErrorKind::InvalidDate));
+            }
+        }
+        Ok(holey_date)
     }
 }
 
-impl Parse for Interval {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        let mut interval = Self::default();
+/// Amounts of years, months, weeks, days, hours, minutes and seconds
+/// with fractions carried over to the smaller units
+struct IntervalFields([u64; 7]);
+
+impl IntervalFields {
+    const SECONDS: usize = 6;
+
+    /// How many of the next smaller unit make up the given one
+    fn carry_factor(unit: usize) -> Option<u64> {
+        match unit {
+            0 => Some(12),
+            1 => None,
+            2 => Some(7),
+            3 => Some(24),
+            4 | 5 => Some(60),
+            _ => None,
+        }
+    }
+
+    /// Parse the interval components down to the `smallest` unit
+    fn parse(
+        pair: Pair<'_, Rule>,
+        smallest: usize,
+    ) -> Result<Self, ParseError> {
+        let mut fields = Self([0; 7]);
         for rec in pair.into_inner() {
-            match rec.as_rule() {
-                Rule::interval_years => {
-                    interval.years = rec.as_str().parse().ok()?;
-                }
-                Rule::interval_months => {
-                    interval.months = rec.as_str().parse().ok()?;
-                }
-                Rule::interval_weeks => {
-                    interval.weeks = rec.as_str().parse().ok()?;
+            let unit = match rec.as_rule() {
+                Rule::interval_years | Rule::other_years => 0,
+                Rule::interval_months | Rule::other_months => 1,
+                Rule::interval_weeks | Rule::other_weeks => 2,
+                Rule::interval_days | Rule::other_days => 3,
+                Rule::interval_hours => 4,
+                Rule::interval_minutes => 5,
+                Rule::interval_seconds => 6,
+                Rule::interval_half_hour => {
+                    fields.0[5] += 30;
+                    continue;
                 }
-                Rule::interval_days => {
-                    interval.days = rec.as_str().parse().ok()?;
+                Rule::interval_quarter_hour => {
+                    fields.0[5] += 15;
+                    continue;
                 }
-                Rule::interval_hours => {
-                    interval.hours = rec.as_str().parse().ok()?;
+                _ => unreachable!(),
+            };
+            fields.add(&rec, unit, smallest)?;
+        }
+        Ok(fields)
+    }
+
+    fn add(
+        &mut self,
+        pair: &Pair<'_, Rule>,
+        mut unit: usize,
+        smallest: usize,
+    ) -> Result<(), ParseError> {
+        let invalid =
+            || ParseError::new(pair.as_span(), ParseErrorKind::InvalidNumber);
+        let (mut numerator, denominator) =
+            parse_decimal(pair).ok_or_else(invalid)?;
+        loop {
+            self.0[unit] = self.0[unit]
+                .checked_add(numerator / denominator)
+                .ok_or_else(invalid)?;
+            numerator %= denominator;
+            if numerator == 0 {
+                return Ok(());
+            }
+            match Self::carry_factor(unit) {
+                Some(factor) if unit < smallest => {
+                    numerator =
+                        numerator.checked_mul(factor).ok_or_else(invalid)?;
+                    unit += 1;
                 }
-                Rule::interval_minutes => {
-                    interval.minutes = rec.as_str().parse().ok()?;
+                // nothing is smaller than a second
+                _ if unit == Self::SECONDS => {
+                    if 2 * numerator >= denominator {
+                        self.0[unit] += 1;
+                    }
+                    return Ok(());
                 }
-                Rule::interval_seconds => {
-                    interval.seconds = rec.as_str().parse().ok()?;
+                _ => {
+                    return Err(ParseError::new(
+                        pair.as_span(),
+                        ParseErrorKind::FractionNotAllowed,
+                    ))
                 }
-                _ => unreachable!(),
             }
         }
-        Some(interval)
+    }
+
+    fn get<T: TryFrom<u64>>(
+        &self,
+        unit: usize,
+        span: Span<'_>,
+    ) -> Result<T, ParseError> {
+        T::try_from(self.0[unit])
+            .map_err(|_| ParseError::new(span, ParseErrorKind::InvalidNumber))
+    }
+}
+
+/// Numerator and denominator of a decimal or spelled out number
+fn parse_decimal(pair: &Pair<'_, Rule>) -> Option<(u64, u64)> {
+    let s = pair.as_str();
+    if let Some(number) = spelled_number(s) {
+        return Some((number.into(), 1));
+    }
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+    let denominator = 10u64.checked_pow(fraction.len().try_into().ok()?)?;
+    let numerator = whole
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(denominator)?
+        .checked_add(fraction.parse().unwrap_or(0))?;
+    Some((numerator, denominator))
+}
+
+impl Parse for Interval {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let span = pair.as_span();
+        let fields = IntervalFields::parse(pair, IntervalFields::SECONDS)?;
+        let interval = Self {
+            years: fields.get(0, span)?,
+            months: fields.get(1, span)?,
+            weeks: fields.get(2, span)?,
+            days: fields.get(3, span)?,
+            hours: fields.get(4, span)?,
+            minutes: fields.get(5, span)?,
+            seconds: fields.get(6, span)?,
+        };
+        if interval.is_zero() {
+            return Err(ParseError::new(span, ParseErrorKind::ZeroInterval));
+        }
+        Ok(interval)
+    }
+}
+
+impl Interval {
+    fn is_zero(&self) -> bool {
+        self.years == 0
+            && self.months == 0
+            && self.weeks == 0
+            && self.days == 0
+            && self.hours == 0
+            && self.minutes == 0
+            && self.seconds == 0
+    }
+}
+
+impl TimeInterval {
+    fn is_zero(&self) -> bool {
+        self.hours == 0 && self.minutes == 0 && self.seconds == 0
+    }
+}
+
+impl DateInterval {
+    fn is_zero(&self) -> bool {
+        self.years == 0 && self.months == 0 && self.weeks == 0 && self.days == 0
     }
 }
 
@@ -217,20 +560,25 @@ impl Weekday {
     }
 }
 
+impl Weekday {
+    fn from_rule(rule: Rule) -> Option<Self> {
+        match rule {
+            Rule::monday => Some(Self::Monday),
+            Rule::tuesday => Some(Self::Tuesday),
+            Rule::wednesday => Some(Self::Wednesday),
+            Rule::thursday => Some(Self::Thursday),
+            Rule::friday => Some(Self::Friday),
+            Rule::saturday => Some(Self::Saturday),
+            Rule::sunday => Some(Self::Sunday),
+            _ => None,
+        }
+    }
+}
+
 impl Parse for Weekday {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        pair.into_inner()
-            .next()
-            .map(|weekday| match weekday.as_rule() {
-                Rule::monday => Self::Monday,
-                Rule::tuesday => Self::Tuesday,
-                Rule::wednesday => Self::Wednesday,
-                Rule::thursday => Self::Thursday,
-                Rule::friday => Self::Friday,
-                Rule::saturday => Self::Saturday,
-                Rule::sunday => Self::Sunday,
-                _ => unreachable!(),
-            })
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let weekday = pair.into_inner().next().unwrap();
+        Ok(Self::from_rule(weekday.as_rule()).unwrap())
     }
 }
 
@@ -248,25 +596,70 @@ impl Weekdays {
     }
 }
 impl Parse for Weekdays {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
         let mut weekdays = Self::none();
         let mut weekday_range = pair.into_inner();
-        let mut weekday_from = weekday_range.next().and_then(Weekday::parse)?;
+        let mut weekday_from = Weekday::parse(weekday_range.next().unwrap())?;
         let weekday_to = weekday_range
             .next()
-            .and_then(Weekday::parse)
+            .map(Weekday::parse)
+            .transpose()?
             .unwrap_or(weekday_from);
         while weekday_from != weekday_to {
             weekdays.push(weekday_from);
             weekday_from = weekday_from.next();
         }
         weekdays.push(weekday_from);
-        Some(weekdays)
+        Ok(weekdays)
+    }
+}
+
+impl Parse for OrdinalWeekday {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let mut ordinal = None;
+        let mut weekdays = Weekdays::none();
+        for rec in pair.into_inner() {
+            match rec.as_rule() {
+                Rule::ordinal_first => ordinal = Some(1),
+                Rule::ordinal_second => ordinal = Some(2),
+                Rule::ordinal_third => ordinal = Some(3),
+                Rule::ordinal_fourth => ordinal = Some(4),
+                Rule::ordinal_fifth => ordinal = Some(5),
+                Rule::ordinal_last => ordinal = Some(-1),
+                rule => weekdays.push(Weekday::from_rule(rule).unwrap()),
+            }
+        }
+        Ok(Self {
+            ordinal: ordinal.unwrap(),
+            weekday: weekdays,
+        })
+    }
+}
+
+impl Parse for RelativeDate {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let rec = pair.into_inner().next().unwrap();
+        match rec.as_rule() {
+            Rule::today => Ok(Self::Today),
+            Rule::tomorrow => Ok(Self::Tomorrow),
+            Rule::day_after_tomorrow => Ok(Self::DayAfterTomorrow),
+            Rule::this_weekday => {
+                let mut weekdays = Weekdays::none();
+                weekdays.push(Weekday::parse(rec)?);
+                Ok(Self::ThisWeekday(weekdays))
+            }
+            Rule::next_weekday => {
+                let mut weekdays = Weekdays::none();
+                weekdays.push(Weekday::parse(rec)?);
+                Ok(Self::NextWeekday(weekdays))
+            }
+            _ => unreachable!(),
+        }
     }
 }
 
 impl Parse for DateRange {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
         let mut date_range = Self::default();
         for rec in pair.into_inner() {
             match rec.as_rule() {
@@ -276,11 +669,27 @@ impl Parse for DateRange {
                 Rule::date_until => {
                     date_range.until = Some(HoleyDate::parse(rec)?);
                 }
-                Rule::date_interval => {
+                Rule::date_interval | Rule::every_other => {
                     date_range.date_divisor =
                         DateDivisor::Interval(DateInterval::parse(rec)?);
                 }
-                Rule::weekdays_range => {
+                Rule::ordinal_weekday => {
+                    date_range.date_divisor = DateDivisor::OrdinalWeekday(
+                        OrdinalWeekday::parse(rec)?,
+                    );
+                }
+                Rule::month_end => {
+                    let days_before = match rec.into_inner().next() {
+                        Some(days) => parse_number(&days)?,
+                        None => 0,
+                    };
+                    date_range.date_divisor =
+                        DateDivisor::MonthEnd(days_before);
+                }
+                Rule::workdays => {
+                    date_range.date_divisor = DateDivisor::Workdays;
+                }
+                Rule::weekdays_range | Rule::weekends => {
                     let weekdays = match date_range.date_divisor {
                         DateDivisor::Weekdays(ref mut w) => w,
                         _ => {
@@ -292,83 +701,110 @@ impl Parse for DateRange {
                             }
                         }
                     };
-                    *weekdays |= Weekdays::parse(rec)?;
+                    *weekdays |= match rec.as_rule() {
+                        Rule::weekends => Weekdays::Saturday | Weekdays::Sunday,
+                        _ => Weekdays::parse(rec)?,
+                    };
                 }
                 _ => unreachable!(),
             }
         }
-        Some(date_range)
+        Ok(date_range)
     }
 }
 
 impl Parse for Time {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
         let mut time = Self::default();
         for time_component in pair.into_inner() {
             match time_component.as_rule() {
-                Rule::hour => {
-                    time.hour = time_component.as_str().parse().ok()?;
+                Rule::hour | Rule::hour12 => {
+                    time.hour = parse_number(&time_component)?;
+                }
+                Rule::am => {
+                    time.hour %= 12;
+                }
+                Rule::pm => {
+                    time.hour = time.hour % 12 + 12;
+                }
+                Rule::noon => {
+                    time.hour = 12;
+                }
+                Rule::midnight => {
+                    time.hour = 0;
                 }
                 Rule::minute => {
-                    time.minute = time_component.as_str().parse().ok()?;
+                    time.minute = parse_number(&time_component)?;
                 }
                 Rule::second => {
-                    time.second = time_component.as_str().parse().ok()?;
+                    time.second = parse_number(&time_component)?;
                 }
                 _ => unreachable!(),
             }
         }
-        Some(time)
+        Ok(time)
     }
 }
 
 impl Parse for TimeInterval {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        let mut time_interval = Self::default();
-        for rec in pair.into_inner() {
-            match rec.as_rule() {
-                Rule::interval_hours => {
-                    time_interval.hours = rec.as_str().parse().ok()?;
-                }
-                Rule::interval_minutes => {
-                    time_interval.minutes = rec.as_str().parse().ok()?;
-                }
-                Rule::interval_seconds => {
-                    time_interval.seconds = rec.as_str().parse().ok()?;
-                }
-                _ => unreachable!(),
-            }
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let span = pair.as_span();
+        let fields = IntervalFields::parse(pair, IntervalFields::SECONDS)?;
+        let time_interval = Self {
+            hours: fields.get(4, span)?,
+            minutes: fields.get(5, span)?,
+            seconds: fields.get(6, span)?,
+        };
+        if time_interval.is_zero() {
+            return Err(ParseError::new(span, ParseErrorKind::ZeroInterval));
         }
-        Some(time_interval)
+        Ok(time_interval)
     }
 }
 
 impl Parse for DateInterval {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        let mut date_interval = Self::default();
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let span = pair.as_span();
+        // days can't be split into smaller units here
+        let fields = IntervalFields::parse(pair, 3)?;
+        let date_interval = Self {
+            years: fields.get(0, span)?,
+            months: fields.get(1, span)?,
+            weeks: fields.get(2, span)?,
+            days: fields.get(3, span)?,
+        };
+        if date_interval.is_zero() {
+            return Err(ParseError::new(span, ParseErrorKind::ZeroInterval));
+        }
+        Ok(date_interval)
+    }
+}
+
+impl Parse for SunTime {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let mut sun_time = Self {
+            event: SunEvent::Sunrise,
+            offset: TimeInterval::default(),
+            before: false,
+        };
         for rec in pair.into_inner() {
             match rec.as_rule() {
-                Rule::interval_years => {
-                    date_interval.years = rec.as_str().parse().ok()?;
-                }
-                Rule::interval_months => {
-                    date_interval.months = rec.as_str().parse().ok()?;
-                }
-                Rule::interval_weeks => {
-                    date_interval.weeks = rec.as_str().parse().ok()?;
-                }
-                Rule::interval_days => {
-                    date_interval.days = rec.as_str().parse().ok()?;
+                Rule::sunrise => sun_time.event = SunEvent::Sunrise,
+                Rule::sunset => sun_time.event = SunEvent::Sunset,
+                Rule::sun_before => sun_time.before = true,
+                Rule::sun_after => sun_time.before = false,
+                Rule::time_interval => {
+                    sun_time.offset = TimeInterval::parse(rec)?;
                 }
                 _ => unreachable!(),
             }
         }
-        Some(date_interval)
+        Ok(sun_time)
     }
 }
 
 impl Parse for TimeRange {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
         let mut time_range = Self::default();
         for rec in pair.into_inner() {
             match rec.as_rule() {
@@ -381,10 +817,73 @@ impl Parse for TimeRange {
                 Rule::time_interval => {
                     time_range.interval = TimeInterval::parse(rec)?;
                 }
+                Rule::random_times => {
+                    time_range.random = Some(1);
+                    if let Some(count) = rec.into_inner().next() {
+                        let count = count.into_inner().next().unwrap();
+                        time_range.random = Some(match count.as_rule() {
+                            Rule::random_once => 1,
+                            Rule::random_twice => 2,
+                            _ => {
+                                let value = parse_number(&count)?;
+                                if value == 0 {
+                                    return Err(ParseError::new(
+                                        count.as_span(),
+                                        ParseErrorKind::ZeroCount,
+                                    ));
+                                }
+                                value
+                            }
+                        });
+                    }
+                }
                 _ => unreachable!(),
             }
         }
-        Some(time_range)
+        Ok(time_range)
+    }
+}
+
+impl Parse for TimeZone {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let rec = pair.into_inner().next().unwrap();
+        match rec.as_rule() {
+            Rule::tz_offset => {
+                let span = rec.as_span();
+                let (mut sign, mut hours, mut minutes) = (1, 0, 0);
+                for component in rec.into_inner() {
+                    match component.as_rule() {
+                        Rule::tz_offset_sign => {
+                            if component.as_str() == "-" {
+                                sign = -1;
+                            }
+                        }
+                        Rule::tz_offset_hours => {
+                            hours = parse_number(&component)?;
+                        }
+                        Rule::tz_offset_minutes => {
+                            minutes = parse_number(&component)?;
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                if hours > 14 || minutes >= 60 {
+                    return Err(ParseError::new(
+                        span,
+                        ParseErrorKind::InvalidNumber,
+                    ));
+                }
+                Ok(Self::Offset(sign * (hours * 60 + minutes)))
+            }
+            Rule::tz_utc => Ok(Self::Name("UTC".to_owned())),
+            Rule::tz_name => {
+                Ok(Self::Name(known_timezone(rec.as_str()).unwrap().to_owned()))
+            }
+            Rule::tz_abbreviation => {
+                Ok(Self::Name(abbreviation_timezone(rec.as_str()).to_owned()))
+            }
+            _ => unreachable!(),
+        }
     }
 }
 
@@ -393,36 +892,226 @@ impl Default for Recurrence {
         // make sure there's at least one date range
         // the inserted holey range will correspond to the current date point
         Self {
+            offset: None,
             dates_patterns: nonempty![DatePattern::Point(HoleyDate::default())],
             time_patterns: vec![],
+            timezone: None,
+            exceptions: vec![],
+            filters: vec![],
+            limit: None,
+            groups: vec![],
+        }
+    }
+}
+
+/// Parse a weekday on a day of a month like friday the 13th
+/// into a monthly date range and the weekday to filter it with
+fn parse_weekday_day(
+    pair: Pair<'_, Rule>,
+) -> Result<(DateRange, Weekdays), ParseError> {
+    let mut weekdays = Weekdays::none();
+    let mut day = None;
+    for rec in pair.into_inner() {
+        match rec.as_rule() {
+            Rule::day => day = Some(parse_number(&rec)?),
+            rule => weekdays.push(Weekday::from_rule(rule).unwrap()),
         }
     }
+    let date_range = DateRange {
+        from: HoleyDate {
+            day,
+            ..Default::default()
+        },
+        until: None,
+        date_divisor: DateDivisor::Interval(DateInterval {
+            months: 1,
+            ..Default::default()
+        }),
+    };
+    Ok((date_range, weekdays))
+}
+
+impl Parse for DateFilter {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        match pair.as_rule() {
+            Rule::filter_weekdays => {
+                let mut weekdays = Weekdays::none();
+                for rec in pair.into_inner() {
+                    weekdays |= match rec.as_rule() {
+                        Rule::weekends => Weekdays::Saturday | Weekdays::Sunday,
+                        _ => Weekdays::parse(rec)?,
+                    };
+                }
+                Ok(Self::Weekdays(weekdays))
+            }
+            Rule::filter_months => {
+                let mut months = vec![];
+                for rec in pair.into_inner() {
+                    let (from, until) = match rec.as_rule() {
+                        Rule::filter_season => {
+                            match rec.into_inner().next().unwrap().as_rule() {
+                                Rule::spring => (3, 5),
+                                Rule::summer => (6, 8),
+                                Rule::autumn => (9, 11),
+                                _ => (12, 2),
+                            }
+                        }
+                        _ => {
+                            let mut range = rec.into_inner().map(|month| {
+                                let rule = month
+                                    .into_inner()
+                                    .next()
+                                    .unwrap()
+                                    .as_rule();
+                                month_from_rule(rule).unwrap()
+                            });
+                            let from = range.next().unwrap();
+                            (from, range.next().unwrap_or(from))
+                        }
+                    };
+                    // a range like nov-feb crosses the new year
+                    let mut month = from;
+                    loop {
+                        months.push(month);
+                        if month == until {
+                            break;
+                        }
+                        month = month % 12 + 1;
+                    }
+                }
+                months.sort();
+                months.dedup();
+                Ok(Self::Months(months))
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Parse a date or a time pattern into the list of its kind
+fn push_pattern(
+    rec: Pair<'_, Rule>,
+    dates_patterns: &mut impl Extend<DatePattern>,
+    time_patterns: &mut Vec<TimePattern>,
+) -> Result<(), ParseError> {
+    match rec.as_rule() {
+        Rule::dates_point => {
+            dates_patterns.extend([DatePattern::Point(HoleyDate::parse(rec)?)]);
+        }
+        Rule::dates_relative => dates_patterns
+            .extend([DatePattern::Relative(RelativeDate::parse(rec)?)]),
+        Rule::dates_range | Rule::group_weekdays => {
+            dates_patterns.extend([DatePattern::Range(DateRange::parse(rec)?)]);
+        }
+        Rule::time_point => {
+            time_patterns.push(TimePattern::Point(Time::parse(rec)?));
+        }
+        Rule::time_range => {
+            time_patterns.push(TimePattern::Range(TimeRange::parse(rec)?));
+        }
+        Rule::time_sun => {
+            time_patterns.push(TimePattern::Sun(SunTime::parse(rec)?));
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+impl Parse for RecurrenceGroup {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        let mut dates_patterns = vec![];
+        let mut time_patterns = vec![];
+        for rec in pair.into_inner() {
+            push_pattern(rec, &mut dates_patterns, &mut time_patterns)?;
+        }
+        Ok(Self {
+            dates_patterns: NonEmpty::from_vec(dates_patterns).unwrap(),
+            time_patterns,
+        })
+    }
 }
 
 impl Parse for Recurrence {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
         let mut recurrence = Self::default();
         for rec in pair.into_inner() {
             match rec.as_rule() {
-                Rule::dates_point => {
+                Rule::recurrence_offset => {
+                    recurrence.offset = Some(DateInterval::parse(
+                        rec.into_inner().next().unwrap(),
+                    )?);
+                }
+                Rule::dates_point
+                | Rule::dates_relative
+                | Rule::dates_range
+                | Rule::time_point
+                | Rule::time_range
+                | Rule::time_sun => {
+                    push_pattern(
+                        rec,
+                        &mut recurrence.dates_patterns,
+                        &mut recurrence.time_patterns,
+                    )?;
+                }
+                Rule::timezone | Rule::iso_timezone => {
+                    recurrence.timezone = Some(TimeZone::parse(rec)?);
+                }
+                Rule::dates_weekday_day => {
+                    let (date_range, weekdays) = parse_weekday_day(rec)?;
                     recurrence
                         .dates_patterns
-                        .push(DatePattern::Point(HoleyDate::parse(rec)?));
+                        .push(DatePattern::Range(date_range));
+                    recurrence.filters.push(DateFilter::Weekdays(weekdays));
+                }
+                Rule::filter_months | Rule::filter_weekdays => {
+                    recurrence.filters.push(DateFilter::parse(rec)?);
+                }
+                Rule::recurrence_group => {
+                    recurrence.groups.push(RecurrenceGroup::parse(rec)?);
+                }
+                Rule::limit_count => {
+                    let value = rec.into_inner().next().unwrap();
+                    let count = parse_number(&value)?;
+                    if count == 0 {
+                        return Err(ParseError::new(
+                            value.as_span(),
+                            ParseErrorKind::ZeroCount,
+                        ));
+                    }
+                    recurrence.limit = Some(RecurrenceLimit::Count(count));
                 }
-                Rule::dates_range => {
+                Rule::limit_duration => {
+                    recurrence.limit = Some(RecurrenceLimit::Duration(
+                        Interval::parse(rec.into_inner().next().unwrap())?,
+                    ));
+                }
+                Rule::except_dates => {
+                    let mut dates = rec.into_inner();
+                    let from = HoleyDate::parse(dates.next().unwrap())?;
+                    let until = HoleyDate::parse(dates.next().unwrap())?;
                     recurrence
-                        .dates_patterns
-                        .push(DatePattern::Range(DateRange::parse(rec)?));
+                        .exceptions
+                        .push(Exception::DateRange(from, until));
                 }
-                Rule::time_point => {
+                Rule::except_date => {
                     recurrence
-                        .time_patterns
-                        .push(TimePattern::Point(Time::parse(rec)?));
+                        .exceptions
+                        .push(Exception::Date(HoleyDate::parse(rec)?));
                 }
-                Rule::time_range => {
+                Rule::weekdays_range => {
                     recurrence
-                        .time_patterns
-                        .push(TimePattern::Range(TimeRange::parse(rec)?));
+                        .exceptions
+                        .push(Exception::Weekdays(Weekdays::parse(rec)?));
+                }
+                Rule::weekends => {
+                    recurrence.exceptions.push(Exception::Weekdays(
+                        Weekdays::Saturday | Weekdays::Sunday,
+                    ));
+                }
+                Rule::except_time => {
+                    recurrence
+                        .exceptions
+                        .push(Exception::Time(Time::parse(rec)?));
                 }
                 _ => unreachable!(),
             }
@@ -431,12 +1120,12 @@ impl Parse for Recurrence {
             recurrence.dates_patterns =
                 NonEmpty::from_vec(recurrence.dates_patterns.tail).unwrap();
         }
-        Some(recurrence)
+        Ok(recurrence)
     }
 }
 
 impl Parse for Countdown {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
         let mut countdown = Self::default();
         for rec in pair.into_inner() {
             match rec.as_rule() {
@@ -446,18 +1135,18 @@ impl Parse for Countdown {
                 _ => unreachable!(),
             }
         }
-        Some(countdown)
+        Ok(countdown)
     }
 }
 
 impl Parse for Description {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
-        Some(Self(pair.as_str().to_string()))
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
+        Ok(Self(pair.as_str().to_string()))
     }
 }
 
 impl Parse for Reminder {
-    fn parse(pair: Pair<'_, Rule>) -> Option<Self> {
+    fn parse(pair: Pair<'_, Rule>) -> Result<Self, ParseError> {
         let mut reminder = Self::default();
         for rec in pair.into_inner() {
             match rec.as_rule() {
@@ -478,17 +1167,188 @@ impl Parse for Reminder {
                 _ => unreachable!(),
             }
         }
-        Some(reminder)
+        Ok(reminder)
+    }
+}
+
+/// Parsed reminder along with the ranges of the text
+/// that make up its description
+type ParsedText<D> = (Result<Reminder, ParseError>, D);
+
+/// Start of the part of the recurrence that belongs to the description,
+/// e.g. a word with a slash that isn't a timezone,
+/// or the limit of a reminder that happens only once
+fn description_start(reminder: &Pair<'_, Rule>) -> Option<usize> {
+    let recurrence = reminder
+        .clone()
+        .into_inner()
+        .find(|rec| rec.as_rule() == Rule::recurrence)?;
+    let recurs = recurrence.clone().into_inner().any(|rec| {
+        matches!(
+            rec.as_rule(),
+            Rule::dates_range
+                | Rule::dates_weekday_day
+                | Rule::time_range
+                | Rule::recurrence_group
+        )
+    });
+    recurrence
+        .into_inner()
+        .find(|rec| match rec.as_rule() {
+            Rule::limit_count | Rule::limit_duration => !recurs,
+            Rule::timezone => {
+                let tz = rec.clone().into_inner().next().unwrap();
+                tz.as_rule() == Rule::tz_name
+                    && known_timezone(tz.as_str()).is_none()
+            }
+            _ => false,
+        })
+        .map(|rec| rec.as_span().start())
+}
+
+/// Parse the reminder whose pattern starts at `offset`,
+/// shifting all the ranges to be relative to the whole text
+fn parse_from(
+    text: &str,
+    offset: usize,
+) -> Result<ParsedText<Option<Range<usize>>>, ParseError> {
+    let pair = ReminderParser::parse(Rule::reminder, &text[offset..])?
+        .next()
+        .unwrap();
+    if let Some(start) = description_start(&pair) {
+        // the rest of the text is the description
+        let start = offset + start;
+        let (reminder, head) = parse_from(&text[..start], offset)?;
+        let start = head.map_or(start, |head| head.start);
+        return Ok((reminder, Some(start..text.trim_end().len())));
+    }
+    let description = pair
+        .clone()
+        .into_inner()
+        .find(|rec| rec.as_rule() == Rule::description)
+        .map(|rec| {
+            offset + rec.as_span().start()..offset + rec.as_span().end()
+        });
+    let reminder = Reminder::parse(pair).map_err(|mut err| {
+        err.span = offset + err.span.start..offset + err.span.end;
+        err
+    });
+    Ok((reminder, description))
+}
+
+/// Byte offsets of the words following the first one
+fn next_word_starts(text: &str) -> impl Iterator<Item = usize> + '_ {
+    let trimmed = text.trim_start();
+    let first = text.len() - trimmed.len();
+    trimmed
+        .char_indices()
+        .zip(trimmed.chars().skip(1))
+        .filter(|&((_, c), next)| c.is_whitespace() && !next.is_whitespace())
+        .map(move |((i, c), _)| first + i + c.len_utf8())
+}
+
+/// Whether the text that failed to parse starts with a broken pattern
+/// rather than with the description: the pattern got past the first word
+/// that isn't a number, the first word is a date or a time, e.g. 2025-13-01,
+/// or it is a misspelled keyword that would start the pattern
+fn starts_with_pattern(text: &str, err: &ParseError) -> bool {
+    let trimmed = text.trim_start();
+    let start = text.len() - trimmed.len();
+    let word = trimmed.split_whitespace().next().unwrap_or_default();
+    let end = start + word.len();
+    // a number is as likely to start the description, e.g. "one more thing"
+    let number = ReminderParser::parse(Rule::interval_value, word)
+        .is_ok_and(|mut pairs| pairs.next().unwrap().as_str() == word);
+    if err.span.start > end && !number {
+        return true;
+    }
+    if word.starts_with(|c: char| c.is_ascii_digit())
+        && word.contains(['-', '.', ':', '/'])
+        && word
+            .chars()
+            .all(|c| c.is_ascii_digit() || "-.:/".contains(c))
+    {
+        return true;
     }
+    misspelled_keyword(word).is_some_and(|keyword| {
+        parse_from(&format!("{keyword}{}", &text[end..]), 0).is_ok()
+    })
+}
+
+/// Parse the text that either starts with the reminder pattern
+/// or has it at the end or in the middle of the description
+fn parse_text(text: &str) -> Result<ParsedText<Vec<Range<usize>>>, ParseError> {
+    let err = match parse_from(text, 0) {
+        Ok((reminder, description)) => {
+            return Ok((reminder, description.into_iter().collect()))
+        }
+        Err(err) => err,
+    };
+    if starts_with_pattern(text, &err) {
+        return Err(err);
+    }
+    let mut inner = None;
+    for start in next_word_starts(text) {
+        // a number after the description is more likely a part of it
+        // than a time or a day, e.g. "read chapter 5 at 10:00"
+        let word = text[start..].split_whitespace().next().unwrap();
+        if word.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let Ok((reminder, tail)) = parse_from(text, start) else {
+            continue;
+        };
+        let head = 0..text[..start].trim_end().len();
+        match tail {
+            // the longest trailing pattern wins
+            None => return Ok((reminder, vec![head])),
+            Some(tail) => {
+                inner.get_or_insert((reminder, vec![head, tail]));
+            }
+        }
+    }
+    inner.ok_or(err)
+}
+
+fn join_description(
+    s: &str,
+    ranges: impl Iterator<Item = Range<usize>>,
+) -> Option<Description> {
+    let words: Vec<_> = ranges.map(|range| &s[range]).collect();
+    (!words.is_empty()).then(|| Description(words.join(" ")))
+}
+
+pub fn parse_reminder(s: &str) -> Result<Reminder, ParseError> {
+    let (reminder, description) = parse_text(s).inspect_err(|err| {
+        log::debug!("{}", err);
+    })?;
+    let mut reminder = reminder?;
+    reminder.description = join_description(s, description.into_iter());
+    Ok(reminder)
 }
 
-pub fn parse_reminder(s: &str) -> Option<Reminder> {
-    Reminder::parse(
-        ReminderParser::parse(Rule::reminder, s)
-            .map_err(|err| {
-                log::debug!("{}", err);
-            })
-            .ok()?
-            .next()?,
-    )
+/// Parse a reminder that may contain keywords of the given language
+/// alongside the English ones
+pub fn parse_reminder_in(
+    s: &str,
+    language: Language,
+) -> Result<Reminder, ParseError> {
+    let translation = Translation::new(s, language);
+    let to_original = |mut err: ParseError| {
+        err.span = translation.original_range(err.span);
+        err
+    };
+    let (reminder, description) = parse_text(&translation.text)
+        .inspect_err(|err| {
+            log::debug!("{}", err);
+        })
+        .map_err(to_original)?;
+    let mut reminder = reminder.map_err(to_original)?;
+    reminder.description = join_description(
+        s,
+        description
+            .into_iter()
+            .map(|range| translation.original_range(range)),
+    );
+    Ok(reminder)
 }
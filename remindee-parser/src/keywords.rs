@@ -0,0 +1,532 @@
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    Dutch,
+    Russian,
+}
+
+const RUSSIAN_KEYWORDS: &[(&str, &str)] = &[
+    // prefixes
+    ("в", "at"),
+    ("во", "at"),
+    ("каждый", "every"),
+    ("каждую", "every"),
+    ("каждое", "every"),
+    ("каждые", "every"),
+    ("каждого", "every"),
+    ("по", "on"),
+    ("через", "in"),
+    ("спустя", "after"),
+    // relative dates
+    ("сегодня", "today"),
+    ("завтра", "tomorrow"),
+    ("послезавтра", "day after tomorrow"),
+    ("полдень", "noon"),
+    ("полночь", "midnight"),
+    // weekdays
+    ("понедельник", "monday"),
+    ("понедельникам", "monday"),
+    ("пн", "monday"),
+    ("вторник", "tuesday"),
+    ("вторникам", "tuesday"),
+    ("вт", "tuesday"),
+    ("среда", "wednesday"),
+    ("среду", "wednesday"),
+    ("средам", "wednesday"),
+    ("ср", "wednesday"),
+    ("четверг", "thursday"),
+    ("четвергам", "thursday"),
+    ("чт", "thursday"),
+    ("пятница", "friday"),
+    ("пятницу", "friday"),
+    ("пятницам", "friday"),
+    ("пт", "friday"),
+    ("суббота", "saturday"),
+    ("субботу", "saturday"),
+    ("субботам", "saturday"),
+    ("сб", "saturday"),
+    ("воскресенье", "sunday"),
+    ("воскресеньям", "sunday"),
+    ("вс", "sunday"),
+    ("будни", "workdays"),
+    ("будням", "workdays"),
+    ("рабочий день", "workday"),
+    ("рабочим дням", "workdays"),
+    ("выходные", "weekends"),
+    ("выходным", "weekends"),
+    // months
+    ("январь", "january"),
+    ("января", "january"),
+    ("янв", "january"),
+    ("февраль", "february"),
+    ("февраля", "february"),
+    ("фев", "february"),
+    ("март", "march"),
+    ("марта", "march"),
+    ("мар", "march"),
+    ("апрель", "april"),
+    ("апреля", "april"),
+    ("апр", "april"),
+    ("май", "may"),
+    ("мая", "may"),
+    ("июнь", "june"),
+    ("июня", "june"),
+    ("июн", "june"),
+    ("июль", "july"),
+    ("июля", "july"),
+    ("июл", "july"),
+    ("август", "august"),
+    ("августа", "august"),
+    ("авг", "august"),
+    ("сентябрь", "september"),
+    ("сентября", "september"),
+    ("сен", "september"),
+    ("сент", "september"),
+    ("октябрь", "october"),
+    ("октября", "october"),
+    ("окт", "october"),
+    ("ноябрь", "november"),
+    ("ноября", "november"),
+    ("ноя", "november"),
+    ("декабрь", "december"),
+    ("декабря", "december"),
+    ("дек", "december"),
+    ("в январе", "in january"),
+    ("в феврале", "in february"),
+    ("в марте", "in march"),
+    ("в апреле", "in april"),
+    ("в мае", "in may"),
+    ("в июне", "in june"),
+    ("в июле", "in july"),
+    ("в августе", "in august"),
+    ("в сентябре", "in september"),
+    ("в октябре", "in october"),
+    ("в ноябре", "in november"),
+    ("в декабре", "in december"),
+    // seasons
+    ("летом", "in summer"),
+    ("осенью", "in autumn"),
+    ("зимой", "in winter"),
+    ("весной", "in spring"),
+    ("если", "if"),
+    // sun events
+    ("восход", "sunrise"),
+    ("восхода", "sunrise"),
+    ("рассвет", "sunrise"),
+    ("рассвета", "sunrise"),
+    ("на рассвете", "at sunrise"),
+    ("закат", "sunset"),
+    ("заката", "sunset"),
+    ("на закате", "at sunset"),
+    ("до", "before"),
+    ("после", "after"),
+    // numbers
+    ("один", "one"),
+    ("одна", "one"),
+    ("одну", "one"),
+    ("одно", "one"),
+    ("два", "two"),
+    ("две", "two"),
+    ("три", "three"),
+    ("четыре", "four"),
+    ("пять", "five"),
+    ("шесть", "six"),
+    ("семь", "seven"),
+    ("восемь", "eight"),
+    ("девять", "nine"),
+    ("десять", "ten"),
+    ("одиннадцать", "eleven"),
+    ("двенадцать", "twelve"),
+    ("тринадцать", "thirteen"),
+    ("четырнадцать", "fourteen"),
+    ("пятнадцать", "fifteen"),
+    ("шестнадцать", "sixteen"),
+    ("семнадцать", "seventeen"),
+    ("восемнадцать", "eighteen"),
+    ("девятнадцать", "nineteen"),
+    ("двадцать", "twenty"),
+    ("тридцать", "thirty"),
+    ("сорок", "forty"),
+    ("пятьдесят", "fifty"),
+    ("шестьдесят", "sixty"),
+    ("каждый второй", "every other"),
+    ("каждую вторую", "every other"),
+    ("каждое второе", "every other"),
+    ("полчаса", "half an hour"),
+    ("пол часа", "half an hour"),
+    ("четверть часа", "quarter of an hour"),
+    // random times
+    ("случайно", "randomly"),
+    ("в случайное время", "randomly"),
+    ("между", "between"),
+    ("и", "and"),
+    ("один раз", "once"),
+    ("дважды", "twice"),
+    ("раз", "times"),
+    ("раза", "times"),
+    // units
+    ("год", "y"),
+    ("года", "y"),
+    ("лет", "y"),
+    ("г", "y"),
+    ("месяц", "mo"),
+    ("месяца", "mo"),
+    ("месяцев", "mo"),
+    ("мес", "mo"),
+    ("неделя", "w"),
+    ("неделю", "w"),
+    ("недели", "w"),
+    ("недель", "w"),
+    ("нед", "w"),
+    ("день", "d"),
+    ("дня", "d"),
+    ("дней", "d"),
+    ("дн", "d"),
+    ("д", "d"),
+    ("час", "h"),
+    ("часа", "h"),
+    ("часов", "h"),
+    ("ч", "h"),
+    ("минута", "m"),
+    ("минуту", "m"),
+    ("минуты", "m"),
+    ("минут", "m"),
+    ("мин", "m"),
+    ("м", "m"),
+    ("секунда", "s"),
+    ("секунду", "s"),
+    ("секунды", "s"),
+    ("секунд", "s"),
+    ("сек", "s"),
+    ("с", "s"),
+];
+
+const DUTCH_KEYWORDS: &[(&str, &str)] = &[
+    // prefixes
+    ("om", "at"),
+    ("elke", "every"),
+    ("elk", "every"),
+    ("iedere", "every"),
+    ("ieder", "every"),
+    ("op", "on"),
+    ("over", "in"),
+    ("na", "after"),
+    // relative dates
+    ("vandaag", "today"),
+    ("morgen", "tomorrow"),
+    ("overmorgen", "day after tomorrow"),
+    ("middernacht", "midnight"),
+    // weekdays
+    ("maandag", "monday"),
+    ("ma", "monday"),
+    ("dinsdag", "tuesday"),
+    ("di", "tuesday"),
+    ("woensdag", "wednesday"),
+    ("wo", "wednesday"),
+    ("donderdag", "thursday"),
+    ("do", "thursday"),
+    ("vrijdag", "friday"),
+    ("vr", "friday"),
+    ("zaterdag", "saturday"),
+    ("za", "saturday"),
+    ("zondag", "sunday"),
+    ("zo", "sunday"),
+    ("werkdag", "workday"),
+    ("werkdagen", "workdays"),
+    ("doordeweeks", "workdays"),
+    // months
+    ("januari", "january"),
+    ("februari", "february"),
+    ("maart", "march"),
+    ("mrt", "march"),
+    ("mei", "may"),
+    ("juni", "june"),
+    ("juli", "july"),
+    ("augustus", "august"),
+    ("oktober", "october"),
+    ("okt", "october"),
+    // seasons
+    ("in de", "in the"),
+    ("zomer", "summer"),
+    ("herfst", "autumn"),
+    ("najaar", "autumn"),
+    ("lente", "spring"),
+    ("voorjaar", "spring"),
+    ("als", "if"),
+    // sun events
+    ("zonsopgang", "sunrise"),
+    ("zonsondergang", "sunset"),
+    ("voor", "before"),
+    // random times
+    ("willekeurig", "randomly"),
+    ("op een willekeurig moment", "randomly"),
+    ("tussen", "between"),
+    ("en", "and"),
+    ("eenmaal", "once"),
+    ("tweemaal", "twice"),
+    ("keer", "times"),
+    ("maal", "times"),
+    // numbers
+    ("een", "one"),
+    ("twee", "two"),
+    ("drie", "three"),
+    ("vier", "four"),
+    ("vijf", "five"),
+    ("zes", "six"),
+    ("zeven", "seven"),
+    ("acht", "eight"),
+    ("negen", "nine"),
+    ("tien", "ten"),
+    ("elf", "eleven"),
+    ("twaalf", "twelve"),
+    ("dertien", "thirteen"),
+    ("veertien", "fourteen"),
+    ("vijftien", "fifteen"),
+    ("zestien", "sixteen"),
+    ("zeventien", "seventeen"),
+    ("achttien", "eighteen"),
+    ("negentien", "nineteen"),
+    ("twintig", "twenty"),
+    ("eenentwintig", "twenty-one"),
+    ("tweeëntwintig", "twenty-two"),
+    ("drieëntwintig", "twenty-three"),
+    ("vierentwintig", "twenty-four"),
+    ("vijfentwintig", "twenty-five"),
+    ("zesentwintig", "twenty-six"),
+    ("zevenentwintig", "twenty-seven"),
+    ("achtentwintig", "twenty-eight"),
+    ("negenentwintig", "twenty-nine"),
+    ("dertig", "thirty"),
+    ("eenendertig", "thirty-one"),
+    ("tweeëndertig", "thirty-two"),
+    ("drieëndertig", "thirty-three"),
+    ("vierendertig", "thirty-four"),
+    ("vijfendertig", "thirty-five"),
+    ("zesendertig", "thirty-six"),
+    ("zevenendertig", "thirty-seven"),
+    ("achtendertig", "thirty-eight"),
+    ("negenendertig", "thirty-nine"),
+    ("veertig", "forty"),
+    ("eenenveertig", "forty-one"),
+    ("tweeënveertig", "forty-two"),
+    ("drieënveertig", "forty-three"),
+    ("vierenveertig", "forty-four"),
+    ("vijfenveertig", "forty-five"),
+    ("zesenveertig", "forty-six"),
+    ("zevenenveertig", "forty-seven"),
+    ("achtenveertig", "forty-eight"),
+    ("negenenveertig", "forty-nine"),
+    ("vijftig", "fifty"),
+    ("eenenvijftig", "fifty-one"),
+    ("tweeënvijftig", "fifty-two"),
+    ("drieënvijftig", "fifty-three"),
+    ("vierenvijftig", "fifty-four"),
+    ("vijfenvijftig", "fifty-five"),
+    ("zesenvijftig", "fifty-six"),
+    ("zevenenvijftig", "fifty-seven"),
+    ("achtenvijftig", "fifty-eight"),
+    ("negenenvijftig", "fifty-nine"),
+    ("zestig", "sixty"),
+    ("om de dag", "every other day"),
+    ("om de week", "every other week"),
+    ("om de maand", "every other month"),
+    ("om de", "every"),
+    ("half uur", "half hour"),
+    ("een half uur", "half an hour"),
+    ("halfuur", "half hour"),
+    ("kwartier", "quarter hour"),
+    ("een kwartier", "a quarter hour"),
+    // units
+    ("jaar", "y"),
+    ("jaren", "y"),
+    ("j", "y"),
+    ("maand", "mo"),
+    ("maanden", "mo"),
+    ("mnd", "mo"),
+    ("weken", "w"),
+    ("dag", "d"),
+    ("dagen", "d"),
+    ("uur", "h"),
+    ("uren", "h"),
+    ("u", "h"),
+    ("minuut", "m"),
+    ("minuten", "m"),
+    ("seconde", "s"),
+    ("seconden", "s"),
+];
+
+impl Language {
+    pub(crate) fn keywords(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::English => &[],
+            Self::Dutch => DUTCH_KEYWORDS,
+            Self::Russian => RUSSIAN_KEYWORDS,
+        }
+    }
+}
+
+struct Segment {
+    translated: Range<usize>,
+    original: Range<usize>,
+    replaced: bool,
+}
+
+/// Text with localized keywords replaced by the English ones
+/// that keeps track of where each piece came from
+pub(crate) struct Translation {
+    pub(crate) text: String,
+    segments: Vec<Segment>,
+}
+
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(char::is_alphabetic)
+}
+
+/// Length of `keyword` if `s` starts with it, ignoring case
+fn match_keyword(s: &str, keyword: &str) -> Option<usize> {
+    let mut chars = s.char_indices();
+    for k in keyword.chars() {
+        let (_, c) = chars.next()?;
+        if !c.to_lowercase().eq(k.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(chars.next().map(|(i, _)| i).unwrap_or(s.len()))
+}
+
+fn is_weekday(keyword: &str) -> bool {
+    [
+        "workday",
+        "workdays",
+        "weekends",
+        "monday",
+        "tuesday",
+        "wednesday",
+        "thursday",
+        "friday",
+        "saturday",
+        "sunday",
+    ]
+    .contains(&keyword)
+}
+
+impl Translation {
+    pub(crate) fn new(s: &str, language: Language) -> Self {
+        let keywords = language.keywords();
+        let mut replacements: Vec<(Range<usize>, &str)> = vec![];
+        let mut prev = None;
+        let mut pos = 0;
+        while let Some(c) = s[pos..].chars().next() {
+            let rest = &s[pos..];
+            let longest = (!is_word_char(prev))
+                .then(|| {
+                    keywords
+                        .iter()
+                        .filter_map(|&(keyword, english)| {
+                            let len = match_keyword(rest, keyword)?;
+                            (!is_word_char(rest[len..].chars().next()))
+                                .then_some((len, english))
+                        })
+                        .max_by_key(|&(len, _)| len)
+                })
+                .flatten();
+            match longest {
+                Some((len, english)) => {
+                    replacements.push((pos..pos + len, english));
+                    prev = rest[..len].chars().last();
+                    pos += len;
+                }
+                None => {
+                    prev = Some(c);
+                    pos += c.len_utf8();
+                }
+            }
+        }
+        // a preposition before a weekday introduces a date, not a time
+        for i in 1..replacements.len() {
+            let (ref prev_range, prev_english) = replacements[i - 1];
+            let (ref range, english) = replacements[i];
+            if prev_english == "at"
+                && is_weekday(english)
+                && s[prev_range.end..range.start].trim().is_empty()
+            {
+                replacements[i - 1].1 = "on";
+            }
+        }
+
+        let mut translation = Self {
+            text: String::with_capacity(s.len()),
+            segments: vec![],
+        };
+        let mut original_pos = 0;
+        for (range, english) in replacements {
+            if original_pos < range.start {
+                translation.push(
+                    original_pos..range.start,
+                    &s[original_pos..range.start],
+                    false,
+                );
+            }
+            original_pos = range.end;
+            translation.push(range, english, true);
+        }
+        if original_pos < s.len() {
+            translation.push(original_pos..s.len(), &s[original_pos..], false);
+        }
+        translation
+    }
+
+    fn push(&mut self, original: Range<usize>, piece: &str, replaced: bool) {
+        let start = self.text.len();
+        self.text.push_str(piece);
+        self.segments.push(Segment {
+            translated: start..self.text.len(),
+            original,
+            replaced,
+        });
+    }
+
+    /// Get the range of the original text
+    /// corresponding to the given range of the translated one
+    pub(crate) fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        let original_len = self
+            .segments
+            .last()
+            .map(|seg| seg.original.end)
+            .unwrap_or(0);
+        let start = self
+            .segments
+            .iter()
+            .find(|seg| seg.translated.contains(&range.start))
+            .map(|seg| {
+                if seg.replaced {
+                    seg.original.start
+                } else {
+                    seg.original.start + range.start - seg.translated.start
+                }
+            })
+            .unwrap_or(original_len);
+        if range.is_empty() {
+            return start..start;
+        }
+        let end = self
+            .segments
+            .iter()
+            .find(|seg| {
+                seg.translated.start < range.end
+                    && range.end <= seg.translated.end
+            })
+            .map(|seg| {
+                if seg.replaced {
+                    seg.original.end
+                } else {
+                    seg.original.start + range.end - seg.translated.start
+                }
+            })
+            .unwrap_or(original_len);
+        start..end
+    }
+}
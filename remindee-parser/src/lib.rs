@@ -1,7 +1,15 @@
+mod correction;
+mod error;
 pub mod grammar;
+mod keywords;
 
+pub use correction::suggest_correction;
+pub use error::{Expected, ParseError, ParseErrorKind};
 pub use grammar::{
-    parse_reminder, Countdown, DateDivisor, DateInterval, DatePattern,
-    DateRange, Description, HoleyDate, Interval, Recurrence, Reminder,
-    ReminderPattern, Time, TimeInterval, TimePattern, TimeRange, Weekdays,
+    parse_reminder, parse_reminder_in, Countdown, DateDivisor, DateFilter,
+    DateInterval, DatePattern, DateRange, Description, Exception, HoleyDate,
+    Interval, OrdinalWeekday, Recurrence, RecurrenceGroup, RecurrenceLimit,
+    RelativeDate, Reminder, ReminderPattern, SunEvent, SunTime, Time,
+    TimeInterval, TimePattern, TimeRange, TimeZone, Weekdays,
 };
+pub use keywords::Language;
@@ -0,0 +1,19 @@
+use chrono::prelude::*;
+#[cfg(any(test, feature = "test-clock"))]
+use std::sync::RwLock;
+
+/// Unix timestamp returned by [`now_time`] in tests
+#[cfg(any(test, feature = "test-clock"))]
+pub static TEST_TIMESTAMP: RwLock<i64> = RwLock::new(0);
+
+#[cfg(not(any(test, feature = "test-clock")))]
+pub fn now_time() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+#[cfg(any(test, feature = "test-clock"))]
+pub fn now_time() -> NaiveDateTime {
+    DateTime::from_timestamp(*TEST_TIMESTAMP.read().unwrap(), 0)
+        .unwrap()
+        .naive_utc()
+}
@@ -0,0 +1,221 @@
+use crate::pattern::{DateInterval, Interval};
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, TimeDelta, Weekday};
+use chronoutil::{is_leap_year, shift_months};
+use nonempty::NonEmpty;
+
+pub(crate) fn normalise_day(year: i32, month: u32, day: u32) -> u32 {
+    if day <= 28 {
+        day
+    } else if month == 2 {
+        28 + is_leap_year(year) as u32
+    } else if day == 31
+        && (month == 4 || month == 6 || month == 9 || month == 11)
+    {
+        30
+    } else {
+        day
+    }
+}
+
+/// The time shifted by the interval, `None` if it's out of range
+pub(crate) fn add_interval(
+    time: NaiveDateTime,
+    interval: &Interval,
+) -> Option<NaiveDateTime> {
+    let years = u32::try_from(interval.years).ok()?.checked_mul(12)?;
+    time.checked_add_months(Months::new(years))?
+        .checked_add_months(Months::new(interval.months))?
+        .checked_add_signed(TimeDelta::try_weeks(interval.weeks.into())?)?
+        .checked_add_signed(TimeDelta::try_days(interval.days.into())?)?
+        .checked_add_signed(TimeDelta::try_hours(interval.hours.into())?)?
+        .checked_add_signed(TimeDelta::try_minutes(interval.minutes.into())?)?
+        .checked_add_signed(TimeDelta::try_seconds(interval.seconds.into())?)
+}
+
+pub(crate) fn add_date_interval(
+    date: NaiveDate,
+    interval: &DateInterval,
+) -> Option<NaiveDate> {
+    add_interval(
+        date.and_hms_opt(0, 0, 0).unwrap(),
+        &Interval {
+            years: interval.years,
+            months: interval.months,
+            weeks: interval.weeks,
+            days: interval.days,
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+        },
+    )
+    .map(|time| time.date())
+}
+
+pub(crate) fn find_nearest_weekday(
+    mut date: NaiveDate,
+    weekdays: NonEmpty<u32>,
+) -> NaiveDate {
+    while !weekdays.contains(&date.weekday().num_days_from_monday()) {
+        date += chrono::Duration::days(1);
+    }
+    date
+}
+
+/// Get the `ordinal`-th given weekday of the month,
+/// counting from the end of the month if `ordinal` is negative
+pub(crate) fn nth_weekday_of_month(
+    year: i32,
+    month: u32,
+    weekday: Weekday,
+    ordinal: i32,
+) -> Option<NaiveDate> {
+    if ordinal > 0 {
+        NaiveDate::from_weekday_of_month_opt(
+            year,
+            month,
+            weekday,
+            ordinal.try_into().ok()?,
+        )
+    } else {
+        let last_day = NaiveDate::from_ymd_opt(
+            year,
+            month,
+            normalise_day(year, month, 31),
+        )?;
+        let last_weekday = last_day
+            - chrono::Duration::days(
+                (7 + last_day.weekday().num_days_from_monday() as i64
+                    - weekday.num_days_from_monday() as i64)
+                    % 7,
+            );
+        let date = last_weekday + chrono::Duration::weeks(ordinal as i64 + 1);
+        (date.month() == month).then_some(date)
+    }
+}
+
+/// Find the nearest date not earlier than `date`
+/// that is the `ordinal`-th given weekday of its month
+pub(crate) fn find_nearest_ordinal_weekday(
+    date: NaiveDate,
+    weekday: Weekday,
+    ordinal: i32,
+) -> Option<NaiveDate> {
+    // every weekday occurs five times in a month at least four times a year
+    (0..12)
+        .map(|i| shift_months(date.with_day(1).unwrap(), i))
+        .filter_map(|month_start| {
+            nth_weekday_of_month(
+                month_start.year(),
+                month_start.month(),
+                weekday,
+                ordinal,
+            )
+        })
+        .find(|&nearest_date| nearest_date >= date)
+}
+
+/// Find the nearest date not earlier than `date`
+/// that is `days_before` days before the last day of its month
+pub(crate) fn find_nearest_month_end(
+    date: NaiveDate,
+    days_before: u32,
+) -> Option<NaiveDate> {
+    (0..12)
+        .map(|i| shift_months(date.with_day(1).unwrap(), i))
+        .filter_map(|month_start| {
+            let last_day =
+                normalise_day(month_start.year(), month_start.month(), 31);
+            month_start.with_day(last_day.checked_sub(days_before)?)
+        })
+        .find(|&nearest_date| nearest_date >= date)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::{NaiveDateTime, NaiveTime, Timelike};
+    use test_case::test_case;
+
+    #[derive(Debug, PartialEq)]
+    struct Time(i32, u32, u32, u32, u32, u32);
+
+    #[test_case(Time(2023, 9, 3, 0, 0, 0),
+                Interval{years: 0, months: 1 , weeks: 0, days: 0, hours: 0, minutes: 0, seconds: 0 }
+                => Time(2023, 10, 3, 0, 0, 0) ;
+                "just increment month")]
+    #[test_case(Time(2023, 1, 31, 0, 0, 0),
+                Interval{years: 0, months: 1 , weeks: 0, days: 0, hours: 0, minutes: 0, seconds: 0 }
+                => Time(2023, 2, 28, 0, 0, 0) ;
+                "day should be clipped")]
+    #[test_case(Time(2023, 12, 31, 0, 0, 0),
+                Interval{years: 0, months: 1 , weeks: 0, days: 0, hours: 0, minutes: 0, seconds: 0 }
+                => Time(2024, 1, 31, 0, 0, 0) ;
+                "increment year")]
+    #[test_case(Time(2023, 5, 15, 0, 0, 0),
+                Interval{years: 0, months: 9 , weeks: 0, days: 0, hours: 0, minutes: 0, seconds: 0 }
+                => Time(2024, 2, 15, 0, 0, 0) ;
+                "increment year and month")]
+    #[test_case(Time(2023, 7, 7, 0, 0, 0),
+                Interval{years: 0, months: 20, weeks: 0, days: 0, hours: 0, minutes: 0, seconds: 0 }
+                => Time(2025, 3, 7, 0, 0, 0) ;
+                "add many months")]
+    #[test_case(Time(2023, 11, 23, 22, 58, 59),
+                Interval{years: 1, months: 1, weeks: 1, days: 1, hours: 1, minutes: 1, seconds: 1 }
+                => Time(2025, 1, 1, 0, 0, 0) ;
+                "add all units")]
+    fn test_add_interval(time: Time, interval: Interval) -> Time {
+        let (year, month, day, hour, minute, second) =
+            (time.0, time.1, time.2, time.3, time.4, time.5);
+        let datetime = NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(year, month, day).unwrap(),
+            NaiveTime::from_hms_opt(hour, minute, second).unwrap(),
+        );
+        let result = add_interval(datetime, &interval).unwrap();
+        Time(
+            result.year(),
+            result.month(),
+            result.day(),
+            result.hour(),
+            result.minute(),
+            result.second(),
+        )
+    }
+
+    #[test_case(Interval{years: 9999999, months: 0, weeks: 0, days: 0, hours: 0, minutes: 0, seconds: 0 } ; "years")]
+    #[test_case(Interval{years: 0, months: u32::MAX, weeks: 0, days: 0, hours: 0, minutes: 0, seconds: 0 } ; "months")]
+    #[test_case(Interval{years: 0, months: 0, weeks: 0, days: u32::MAX, hours: 0, minutes: 0, seconds: 0 } ; "days")]
+    fn test_add_interval_out_of_range(interval: Interval) {
+        let datetime = NaiveDate::from_ymd_opt(2023, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(add_interval(datetime, &interval), None);
+    }
+
+    #[test_case(2007, 2, Weekday::Mon, 1 => Some(NaiveDate::from_ymd_opt(2007, 2, 5).unwrap()) ; "first monday")]
+    #[test_case(2007, 2, Weekday::Thu, 1 => Some(NaiveDate::from_ymd_opt(2007, 2, 1).unwrap()) ; "first day is the weekday")]
+    #[test_case(2007, 2, Weekday::Fri, -1 => Some(NaiveDate::from_ymd_opt(2007, 2, 23).unwrap()) ; "last friday")]
+    #[test_case(2008, 2, Weekday::Fri, -1 => Some(NaiveDate::from_ymd_opt(2008, 2, 29).unwrap()) ; "last friday of leap february")]
+    #[test_case(2007, 2, Weekday::Fri, 5 => None ; "no fifth friday")]
+    fn test_nth_weekday_of_month(
+        year: i32,
+        month: u32,
+        weekday: Weekday,
+        ordinal: i32,
+    ) -> Option<NaiveDate> {
+        nth_weekday_of_month(year, month, weekday, ordinal)
+    }
+
+    #[test_case(NaiveDate::from_ymd_opt(2007, 2, 2).unwrap(), 0 => Some(NaiveDate::from_ymd_opt(2007, 2, 28).unwrap()) ; "last day of february")]
+    #[test_case(NaiveDate::from_ymd_opt(2008, 2, 2).unwrap(), 0 => Some(NaiveDate::from_ymd_opt(2008, 2, 29).unwrap()) ; "last day of leap february")]
+    #[test_case(NaiveDate::from_ymd_opt(2007, 2, 26).unwrap(), 3 => Some(NaiveDate::from_ymd_opt(2007, 3, 28).unwrap()) ; "already passed this month")]
+    #[test_case(NaiveDate::from_ymd_opt(2007, 4, 1).unwrap(), 3 => Some(NaiveDate::from_ymd_opt(2007, 4, 27).unwrap()) ; "thirty days month")]
+    #[test_case(NaiveDate::from_ymd_opt(2007, 2, 1).unwrap(), 29 => Some(NaiveDate::from_ymd_opt(2007, 3, 2).unwrap()) ; "skip too short month")]
+    #[test_case(NaiveDate::from_ymd_opt(2007, 2, 1).unwrap(), 31 => None ; "no such day")]
+    fn test_find_nearest_month_end(
+        date: NaiveDate,
+        days_before: u32,
+    ) -> Option<NaiveDate> {
+        find_nearest_month_end(date, days_before)
+    }
+}
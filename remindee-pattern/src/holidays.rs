@@ -0,0 +1,214 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::date;
+
+/// Rule that yields the date of a public holiday in a given year
+enum Holiday {
+    /// Same month and day every year
+    Fixed(u32, u32),
+    /// Number of days after Easter Sunday
+    Easter(i64),
+    /// The `ordinal`-th weekday of the month, counting from the end
+    /// of the month if `ordinal` is negative
+    Weekday(u32, Weekday, i32),
+}
+
+impl Holiday {
+    fn date(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            Holiday::Fixed(month, day) => {
+                NaiveDate::from_ymd_opt(year, month, day)
+            }
+            Holiday::Easter(days) => {
+                Some(easter_sunday(year)? + Duration::days(days))
+            }
+            Holiday::Weekday(month, weekday, ordinal) => {
+                date::nth_weekday_of_month(year, month, weekday, ordinal)
+            }
+        }
+    }
+}
+
+const GERMAN_HOLIDAYS: &[Holiday] = &[
+    Holiday::Fixed(1, 1),
+    Holiday::Easter(-2),
+    Holiday::Easter(1),
+    Holiday::Fixed(5, 1),
+    Holiday::Easter(39),
+    Holiday::Easter(50),
+    Holiday::Fixed(10, 3),
+    Holiday::Fixed(12, 25),
+    Holiday::Fixed(12, 26),
+];
+
+const DUTCH_HOLIDAYS: &[Holiday] = &[
+    Holiday::Fixed(1, 1),
+    Holiday::Easter(0),
+    Holiday::Easter(1),
+    Holiday::Fixed(4, 27),
+    Holiday::Fixed(5, 5),
+    Holiday::Easter(39),
+    Holiday::Easter(49),
+    Holiday::Easter(50),
+    Holiday::Fixed(12, 25),
+    Holiday::Fixed(12, 26),
+];
+
+const RUSSIAN_HOLIDAYS: &[Holiday] = &[
+    Holiday::Fixed(1, 1),
+    Holiday::Fixed(1, 2),
+    Holiday::Fixed(1, 3),
+    Holiday::Fixed(1, 4),
+    Holiday::Fixed(1, 5),
+    Holiday::Fixed(1, 6),
+    Holiday::Fixed(1, 7),
+    Holiday::Fixed(1, 8),
+    Holiday::Fixed(2, 23),
+    Holiday::Fixed(3, 8),
+    Holiday::Fixed(5, 1),
+    Holiday::Fixed(5, 9),
+    Holiday::Fixed(6, 12),
+    Holiday::Fixed(11, 4),
+];
+
+const BRITISH_HOLIDAYS: &[Holiday] = &[
+    Holiday::Fixed(1, 1),
+    Holiday::Easter(-2),
+    Holiday::Easter(1),
+    Holiday::Weekday(5, Weekday::Mon, 1),
+    Holiday::Weekday(5, Weekday::Mon, -1),
+    Holiday::Weekday(8, Weekday::Mon, -1),
+    Holiday::Fixed(12, 25),
+    Holiday::Fixed(12, 26),
+];
+
+const AMERICAN_HOLIDAYS: &[Holiday] = &[
+    Holiday::Fixed(1, 1),
+    Holiday::Weekday(1, Weekday::Mon, 3),
+    Holiday::Weekday(2, Weekday::Mon, 3),
+    Holiday::Weekday(5, Weekday::Mon, -1),
+    Holiday::Fixed(6, 19),
+    Holiday::Fixed(7, 4),
+    Holiday::Weekday(9, Weekday::Mon, 1),
+    Holiday::Weekday(10, Weekday::Mon, 2),
+    Holiday::Fixed(11, 11),
+    Holiday::Weekday(11, Weekday::Thu, 4),
+    Holiday::Fixed(12, 25),
+];
+
+/// Country whose public holidays are skipped by the workdays divisor
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Country {
+    #[serde(rename = "DE")]
+    Germany,
+    #[serde(rename = "NL")]
+    Netherlands,
+    #[serde(rename = "RU")]
+    Russia,
+    #[serde(rename = "GB")]
+    UnitedKingdom,
+    #[serde(rename = "US")]
+    UnitedStates,
+}
+
+impl Country {
+    pub const fn code(self) -> &'static str {
+        match self {
+            Country::Germany => "DE",
+            Country::Netherlands => "NL",
+            Country::Russia => "RU",
+            Country::UnitedKingdom => "GB",
+            Country::UnitedStates => "US",
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Country::Germany => "🇩🇪 Deutschland",
+            Country::Netherlands => "🇳🇱 Nederland",
+            Country::Russia => "🇷🇺 Россия",
+            Country::UnitedKingdom => "🇬🇧 United Kingdom",
+            Country::UnitedStates => "🇺🇸 United States",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "DE" => Some(Self::Germany),
+            "NL" => Some(Self::Netherlands),
+            "RU" => Some(Self::Russia),
+            "GB" => Some(Self::UnitedKingdom),
+            "US" => Some(Self::UnitedStates),
+            _ => None,
+        }
+    }
+
+    const fn holidays(self) -> &'static [Holiday] {
+        match self {
+            Country::Germany => GERMAN_HOLIDAYS,
+            Country::Netherlands => DUTCH_HOLIDAYS,
+            Country::Russia => RUSSIAN_HOLIDAYS,
+            Country::UnitedKingdom => BRITISH_HOLIDAYS,
+            Country::UnitedStates => AMERICAN_HOLIDAYS,
+        }
+    }
+
+    pub(crate) fn is_holiday(self, date: NaiveDate) -> bool {
+        self.holidays()
+            .iter()
+            .any(|holiday| holiday.date(date.year()) == Some(date))
+    }
+}
+
+pub const COUNTRIES: &[Country] = &[
+    Country::Germany,
+    Country::Netherlands,
+    Country::Russia,
+    Country::UnitedKingdom,
+    Country::UnitedStates,
+];
+
+/// Date of the Easter Sunday in the Gregorian calendar
+/// (anonymous Gregorian algorithm)
+pub(crate) fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(2007, NaiveDate::from_ymd_opt(2007, 4, 8).unwrap() ; "2007")]
+    #[test_case(2024, NaiveDate::from_ymd_opt(2024, 3, 31).unwrap() ; "2024")]
+    #[test_case(2025, NaiveDate::from_ymd_opt(2025, 4, 20).unwrap() ; "2025")]
+    #[test_case(2038, NaiveDate::from_ymd_opt(2038, 4, 25).unwrap() ; "latest")]
+    fn test_easter_sunday(year: i32, expected: NaiveDate) {
+        assert_eq!(easter_sunday(year), Some(expected));
+    }
+
+    #[test_case(Country::Netherlands, NaiveDate::from_ymd_opt(2025, 6, 9).unwrap(), true ; "whit monday")]
+    #[test_case(Country::Germany, NaiveDate::from_ymd_opt(2025, 4, 18).unwrap(), true ; "good friday")]
+    #[test_case(Country::UnitedStates, NaiveDate::from_ymd_opt(2025, 11, 27).unwrap(), true ; "thanksgiving")]
+    #[test_case(Country::UnitedKingdom, NaiveDate::from_ymd_opt(2025, 8, 25).unwrap(), true ; "summer bank holiday")]
+    #[test_case(Country::Russia, NaiveDate::from_ymd_opt(2025, 1, 7).unwrap(), true ; "orthodox christmas")]
+    #[test_case(Country::Russia, NaiveDate::from_ymd_opt(2025, 4, 21).unwrap(), false ; "not easter monday")]
+    fn test_is_holiday(country: Country, date: NaiveDate, expected: bool) {
+        assert_eq!(country.is_holiday(date), expected);
+    }
+}
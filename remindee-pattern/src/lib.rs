@@ -0,0 +1,15 @@
+mod clock;
+mod date;
+mod holidays;
+mod pattern;
+mod sun;
+
+pub use clock::now_time;
+#[cfg(any(test, feature = "test-clock"))]
+pub use clock::TEST_TIMESTAMP;
+pub use holidays::{Country, COUNTRIES};
+pub use pattern::{
+    uses_sun_times, Calendar, DstGap, DstOverlap, DstPolicy, Occurrences,
+    Pattern, Tz,
+};
+pub use sun::Location;
@@ -0,0 +1,3543 @@
+use std::cmp::max;
+use std::fmt::Formatter;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bitmask_enum::bitmask;
+use chrono::offset::TimeZone;
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono::LocalResult;
+use chronoutil::{shift_months, shift_years};
+use nonempty::{nonempty, NonEmpty};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::now_time;
+use crate::date;
+use crate::holidays::Country;
+use crate::sun::{Location, SunEvent};
+use remindee_parser as grammar;
+
+const MAX_SKIPPED_OCCURRENCES: usize = 1000;
+
+/// Timezone of a pattern, the offsets that have no zone of their own
+/// like UTC+5:30 are kept as is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tz {
+    Named(chrono_tz::Tz),
+    Offset(FixedOffset),
+}
+
+/// What to do with a local time skipped by a DST transition
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum DstGap {
+    /// Keep the offset from before the transition, e.g. 02:30 becomes
+    /// 03:30 when the clocks go from 02:00 to 03:00
+    #[default]
+    #[serde(rename = "fwd")]
+    ShiftForward,
+    #[serde(rename = "skip")]
+    Skip,
+}
+
+/// Which of the instants of a local time repeated by a DST transition
+/// to take
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum DstOverlap {
+    #[default]
+    #[serde(rename = "early")]
+    Earlier,
+    #[serde(rename = "late")]
+    Later,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct DstPolicy {
+    #[serde(rename = "gap", default)]
+    pub gap: DstGap,
+    #[serde(rename = "ovl", default)]
+    pub overlap: DstOverlap,
+}
+
+/// The user's settings that the occurrences are computed with, looked up
+/// when they're computed so that changing them applies to every reminder
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Calendar {
+    /// Country whose holidays are skipped by the workdays divisors
+    pub holidays: Option<Country>,
+    pub dst: DstPolicy,
+    /// Where the sun times are computed for
+    pub location: Option<Location>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Interval {
+    #[serde(rename = "y")]
+    pub(crate) years: i32,
+    #[serde(rename = "mo")]
+    pub(crate) months: u32,
+    #[serde(rename = "w")]
+    pub(crate) weeks: u32,
+    #[serde(rename = "d")]
+    pub(crate) days: u32,
+    #[serde(rename = "h")]
+    pub(crate) hours: u32,
+    #[serde(rename = "m")]
+    pub(crate) minutes: u32,
+    #[serde(rename = "s")]
+    pub(crate) seconds: u32,
+}
+
+#[bitmask(u8)]
+#[derive(Serialize, Deserialize)]
+pub(crate) enum Weekdays {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+pub(crate) struct OrdinalWeekday {
+    #[serde(rename = "n")]
+    pub(crate) ordinal: i32,
+    #[serde(rename = "wd")]
+    pub(crate) weekday: Weekdays,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum DateDivisor {
+    Weekdays(Weekdays),
+    Interval(DateInterval),
+    OrdinalWeekday(OrdinalWeekday),
+    MonthEnd(u32),
+    Workdays,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct DateRange {
+    pub(crate) from: NaiveDate,
+    pub(crate) until: Option<NaiveDate>,
+    #[serde(rename = "div")]
+    pub(crate) date_divisor: DateDivisor,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum DatePattern {
+    Point(NaiveDate),
+    Range(DateRange),
+}
+
+struct Time;
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+pub(crate) struct TimeInterval {
+    #[serde(rename = "h")]
+    pub(crate) hours: u32,
+    #[serde(rename = "m")]
+    pub(crate) minutes: u32,
+    #[serde(rename = "s")]
+    pub(crate) seconds: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+pub(crate) struct DateInterval {
+    #[serde(rename = "y")]
+    pub(crate) years: i32,
+    #[serde(rename = "mo")]
+    pub(crate) months: u32,
+    #[serde(rename = "w")]
+    pub(crate) weeks: u32,
+    #[serde(rename = "d")]
+    pub(crate) days: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+pub(crate) struct TimeRange {
+    pub(crate) from: Option<NaiveTime>,
+    pub(crate) until: Option<NaiveTime>,
+    #[serde(rename = "int")]
+    pub(crate) interval: TimeInterval,
+    #[serde(rename = "rnd", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) random: Option<RandomTimes>,
+}
+
+/// Random times in a time range, derived from the seed and the date
+/// so that they don't change between restarts
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+pub(crate) struct RandomTimes {
+    #[serde(rename = "n")]
+    pub(crate) count: u32,
+    pub(crate) seed: u64,
+}
+
+/// Time of a sun event shifted by the offset in seconds
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+pub(crate) struct SunTime {
+    #[serde(rename = "ev")]
+    pub(crate) event: SunEvent,
+    #[serde(rename = "off")]
+    pub(crate) offset: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum TimePattern {
+    Point(NaiveTime),
+    Range(TimeRange),
+    Sun(SunTime),
+}
+
+/// Date of an exception, recurring every year if the year is omitted
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub(crate) struct ExceptionDate {
+    #[serde(rename = "y")]
+    pub(crate) year: Option<i32>,
+    #[serde(rename = "mo")]
+    pub(crate) month: u32,
+    #[serde(rename = "d")]
+    pub(crate) day: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
+pub(crate) enum Exception {
+    Weekdays(Weekdays),
+    Dates(ExceptionDate, ExceptionDate),
+    Time(NaiveTime),
+}
+
+/// Constraint that the dates of all occurrences must satisfy
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) enum DateFilter {
+    Months(Vec<u32>),
+    Weekdays(Weekdays),
+}
+
+/// Dates with their own times
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct RecurrenceGroup {
+    #[serde(rename = "dates")]
+    pub(crate) dates_patterns: Vec<DatePattern>,
+    #[serde(rename = "times")]
+    pub(crate) time_patterns: Vec<TimePattern>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Recurrence {
+    #[serde(rename = "dates")]
+    pub(crate) dates_patterns: Vec<DatePattern>,
+    #[serde(rename = "times")]
+    pub(crate) time_patterns: Vec<TimePattern>,
+    /// Groups of dates with their own times besides the first one
+    #[serde(rename = "groups", default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) groups: Vec<RecurrenceGroup>,
+    #[serde(rename = "exc", default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) exceptions: Vec<Exception>,
+    #[serde(rename = "only", default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) filters: Vec<DateFilter>,
+    #[serde(rename = "tz")]
+    pub(crate) timezone: Tz,
+    /// Number of occurrences left, the scheduled one included
+    #[serde(rename = "left", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) remaining: Option<u32>,
+    /// UTC time after which there are no more occurrences
+    #[serde(rename = "end", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) end: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Countdown {
+    #[serde(rename = "from")]
+    pub(crate) time_from: NaiveDateTime,
+    #[serde(rename = "dur")]
+    pub(crate) durations: Vec<Interval>,
+    #[serde(rename = "tz")]
+    pub(crate) timezone: Tz,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Pattern {
+    Recurrence(Recurrence),
+    Countdown(Countdown),
+}
+
+/// Occurrences of a pattern after some time, see [`Pattern::occurrences`]
+pub struct Occurrences<'a> {
+    pattern: &'a Pattern,
+    calendar: Calendar,
+    cur: NaiveDateTime,
+    /// Number of the occurrences left to yield if the pattern is limited
+    remaining: Option<u32>,
+}
+
+trait DateDisplay {
+    fn relfmt<D: Datelike>(
+        &self,
+        f: &mut Formatter<'_>,
+        now: &D,
+    ) -> Result<bool, std::fmt::Error>;
+}
+
+pub(crate) fn fill_date_holes(
+    holey_date: &grammar::HoleyDate,
+    lower_bound: NaiveDate,
+) -> Option<NaiveDate> {
+    let year = holey_date.year.unwrap_or(lower_bound.year());
+    let month = holey_date.month.unwrap_or(lower_bound.month());
+    let day = holey_date.day.unwrap_or(lower_bound.day());
+    let day = date::normalise_day(year, month, day);
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+    if date >= lower_bound {
+        return Some(date);
+    }
+    if holey_date.day.is_none() && date + Duration::days(1) > lower_bound {
+        Some(date + Duration::days(1))
+    } else if holey_date.month.is_none() && shift_months(date, 1) > lower_bound
+    {
+        Some(shift_months(date, 1))
+    } else if shift_years(date, 1) > lower_bound {
+        Some(shift_years(date, 1))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn resolve_relative_date(
+    relative_date: &grammar::RelativeDate,
+    today: NaiveDate,
+) -> NaiveDate {
+    match *relative_date {
+        grammar::RelativeDate::Today => today,
+        grammar::RelativeDate::Tomorrow => today + Duration::days(1),
+        grammar::RelativeDate::DayAfterTomorrow => today + Duration::days(2),
+        grammar::RelativeDate::ThisWeekday(weekday) => {
+            date::find_nearest_weekday(
+                today,
+                nonempty![weekday.bits().trailing_zeros()],
+            )
+        }
+        grammar::RelativeDate::NextWeekday(weekday) => {
+            date::find_nearest_weekday(
+                today + Duration::days(1),
+                nonempty![weekday.bits().trailing_zeros()],
+            )
+        }
+    }
+}
+
+impl Serialize for Tz {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Named(tz) => serializer.serialize_str(tz.name()),
+            Self::Offset(offset) => serializer.collect_str(offset),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Tz {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.parse() {
+            Ok(tz) => Ok(Self::Named(tz)),
+            Err(_) => s
+                .parse()
+                .map(Self::Offset)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl std::fmt::Display for Tz {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Named(tz) => write!(f, "{tz}"),
+            Self::Offset(offset) => write!(f, "UTC{offset}"),
+        }
+    }
+}
+
+impl Tz {
+    fn from_grammar(timezone: &grammar::TimeZone) -> Option<Self> {
+        match *timezone {
+            grammar::TimeZone::Name(ref name) => chrono_tz::TZ_VARIANTS
+                .iter()
+                .find(|tz| tz.name().eq_ignore_ascii_case(name))
+                .copied()
+                .map(Self::Named),
+            grammar::TimeZone::Offset(0) => Some(Self::Named(chrono_tz::UTC)),
+            grammar::TimeZone::Offset(minutes) => {
+                FixedOffset::east_opt(minutes * 60).map(Self::Offset)
+            }
+        }
+    }
+
+    /// Local time of the UTC one
+    pub fn to_local(&self, time: &NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Self::Named(tz) => tz.from_utc_datetime(time).naive_local(),
+            Self::Offset(offset) => *time + *offset,
+        }
+    }
+
+    fn local_to_utc(
+        &self,
+        time: &NaiveDateTime,
+        dst: DstPolicy,
+    ) -> Option<NaiveDateTime> {
+        let tz = match self {
+            Self::Named(tz) => tz,
+            Self::Offset(offset) => return Some(*time - *offset),
+        };
+        match tz.from_local_datetime(time) {
+            LocalResult::Single(dt) => Some(dt.naive_utc()),
+            LocalResult::Ambiguous(earlier, later) => match dst.overlap {
+                DstOverlap::Earlier => Some(earlier.naive_utc()),
+                DstOverlap::Later => Some(later.naive_utc()),
+            },
+            LocalResult::None => match dst.gap {
+                DstGap::ShiftForward => {
+                    let offset = tz
+                        .offset_from_local_datetime(
+                            &(*time - Duration::days(1)),
+                        )
+                        .earliest()?
+                        .fix();
+                    Some(*time - offset)
+                }
+                DstGap::Skip => None,
+            },
+        }
+    }
+}
+
+impl DstGap {
+    pub const fn code(self) -> &'static str {
+        match self {
+            DstGap::ShiftForward => "fwd",
+            DstGap::Skip => "skip",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "fwd" => Some(Self::ShiftForward),
+            "skip" => Some(Self::Skip),
+            _ => None,
+        }
+    }
+}
+
+impl DstOverlap {
+    pub const fn code(self) -> &'static str {
+        match self {
+            DstOverlap::Earlier => "early",
+            DstOverlap::Later => "late",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "early" => Some(Self::Earlier),
+            "late" => Some(Self::Later),
+            _ => None,
+        }
+    }
+}
+
+impl From<grammar::Interval> for Interval {
+    fn from(interval: grammar::Interval) -> Self {
+        Self {
+            years: interval.years,
+            months: interval.months,
+            weeks: interval.weeks,
+            days: interval.days,
+            hours: interval.hours,
+            minutes: interval.minutes,
+            seconds: interval.seconds,
+        }
+    }
+}
+
+impl Weekdays {
+    fn from_single_weekday(weekday: grammar::Weekdays) -> Self {
+        match weekday {
+            grammar::Weekdays::Monday => Self::Monday,
+            grammar::Weekdays::Tuesday => Self::Tuesday,
+            grammar::Weekdays::Wednesday => Self::Wednesday,
+            grammar::Weekdays::Thursday => Self::Thursday,
+            grammar::Weekdays::Friday => Self::Friday,
+            grammar::Weekdays::Saturday => Self::Saturday,
+            grammar::Weekdays::Sunday => Self::Sunday,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl From<grammar::Weekdays> for Weekdays {
+    fn from(weekdays: grammar::Weekdays) -> Self {
+        let mut result = Weekdays::none();
+        for weekday in [
+            grammar::Weekdays::Monday,
+            grammar::Weekdays::Tuesday,
+            grammar::Weekdays::Wednesday,
+            grammar::Weekdays::Thursday,
+            grammar::Weekdays::Friday,
+            grammar::Weekdays::Saturday,
+            grammar::Weekdays::Sunday,
+        ] {
+            if weekdays.contains(weekday) {
+                result |= Self::from_single_weekday(weekday);
+            }
+        }
+        result
+    }
+}
+
+impl From<grammar::DateDivisor> for DateDivisor {
+    fn from(date_divisor: grammar::DateDivisor) -> Self {
+        match date_divisor {
+            grammar::DateDivisor::Weekdays(weekdays) => {
+                Self::Weekdays(weekdays.into())
+            }
+            grammar::DateDivisor::Interval(interval) => {
+                Self::Interval(interval.into())
+            }
+            grammar::DateDivisor::OrdinalWeekday(ordinal_weekday) => {
+                Self::OrdinalWeekday(ordinal_weekday.into())
+            }
+            grammar::DateDivisor::MonthEnd(days_before) => {
+                Self::MonthEnd(days_before)
+            }
+            grammar::DateDivisor::Workdays => Self::Workdays,
+        }
+    }
+}
+
+impl From<grammar::OrdinalWeekday> for OrdinalWeekday {
+    fn from(ordinal_weekday: grammar::OrdinalWeekday) -> Self {
+        Self {
+            ordinal: ordinal_weekday.ordinal,
+            weekday: ordinal_weekday.weekday.into(),
+        }
+    }
+}
+
+impl OrdinalWeekday {
+    fn chrono_weekday(&self) -> Weekday {
+        Weekday::try_from(self.weekday.bits().trailing_zeros() as u8).unwrap()
+    }
+}
+
+impl DateRange {
+    pub(crate) fn get_nearest_date(
+        &self,
+        date: NaiveDate,
+        holidays: Option<Country>,
+    ) -> Option<NaiveDate> {
+        let nearest_date = match self.date_divisor {
+            DateDivisor::Weekdays(weekdays) => {
+                let weekdays = (0..7)
+                    .filter(|i| weekdays.bits() & (1 << i) != 0)
+                    .collect::<Vec<_>>();
+                date::find_nearest_weekday(
+                    max(date, self.from),
+                    NonEmpty::from_vec(weekdays).unwrap(),
+                )
+            }
+            DateDivisor::OrdinalWeekday(ordinal_weekday) => {
+                date::find_nearest_ordinal_weekday(
+                    max(date, self.from),
+                    ordinal_weekday.chrono_weekday(),
+                    ordinal_weekday.ordinal,
+                )?
+            }
+            DateDivisor::MonthEnd(days_before) => {
+                date::find_nearest_month_end(max(date, self.from), days_before)?
+            }
+            DateDivisor::Workdays => {
+                let mut nearest_date = max(date, self.from);
+                while nearest_date.weekday().number_from_monday() > 5
+                    || holidays.is_some_and(|c| c.is_holiday(nearest_date))
+                {
+                    nearest_date += Duration::days(1);
+                }
+                nearest_date
+            }
+            DateDivisor::Interval(int) => {
+                let mut nearest_date = self.from;
+                while nearest_date < date {
+                    nearest_date = date::add_date_interval(nearest_date, &int)?;
+                }
+                nearest_date
+            }
+        };
+        self.until
+            .is_none_or(|until| nearest_date <= until)
+            .then_some(nearest_date)
+    }
+}
+
+impl Time {
+    fn from(time: &grammar::Time) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(time.hour, time.minute, time.second)
+    }
+}
+
+impl From<grammar::TimeInterval> for TimeInterval {
+    fn from(time_interval: grammar::TimeInterval) -> Self {
+        Self {
+            hours: time_interval.hours,
+            minutes: time_interval.minutes,
+            seconds: time_interval.seconds,
+        }
+    }
+}
+
+impl From<TimeInterval> for Duration {
+    fn from(int: TimeInterval) -> Self {
+        Duration::hours(int.hours as i64)
+            + Duration::minutes(int.minutes as i64)
+            + Duration::seconds(int.seconds as i64)
+    }
+}
+
+impl From<grammar::DateInterval> for DateInterval {
+    fn from(date_interval: grammar::DateInterval) -> Self {
+        Self {
+            years: date_interval.years,
+            months: date_interval.months,
+            weeks: date_interval.weeks,
+            days: date_interval.days,
+        }
+    }
+}
+
+impl From<grammar::TimeRange> for TimeRange {
+    fn from(time_range: grammar::TimeRange) -> Self {
+        let from = time_range.from.and_then(|ref time| Time::from(time));
+        let until = time_range.until.and_then(|ref time| Time::from(time));
+        let interval = time_range.interval.into();
+        let random = time_range.random.map(|count| RandomTimes {
+            count,
+            seed: new_seed(),
+        });
+        Self {
+            from,
+            until,
+            interval,
+            random,
+        }
+    }
+}
+
+impl TimePattern {
+    fn from(time_pattern: grammar::TimePattern) -> Option<Self> {
+        match time_pattern {
+            grammar::TimePattern::Point(ref time) => {
+                Time::from(time).map(Self::Point)
+            }
+            grammar::TimePattern::Range(time_range) => {
+                Some(Self::Range(time_range.into()))
+            }
+            grammar::TimePattern::Sun(sun_time) => {
+                Some(Self::Sun(sun_time.into()))
+            }
+        }
+    }
+}
+
+impl From<grammar::SunTime> for SunTime {
+    fn from(sun_time: grammar::SunTime) -> Self {
+        let event = match sun_time.event {
+            grammar::SunEvent::Sunrise => SunEvent::Sunrise,
+            grammar::SunEvent::Sunset => SunEvent::Sunset,
+        };
+        let offset =
+            Duration::from(TimeInterval::from(sun_time.offset)).num_seconds();
+        Self {
+            event,
+            offset: if sun_time.before { -offset } else { offset },
+        }
+    }
+}
+
+impl SunTime {
+    /// Local time of the shifted sun event on `date`
+    fn time_on(
+        &self,
+        date: NaiveDate,
+        location: &Location,
+        tz: Tz,
+    ) -> Option<NaiveDateTime> {
+        let time = location.sun_event(self.event, date)?;
+        Some(tz.to_local(&time) + Duration::seconds(self.offset))
+    }
+}
+
+fn uses_sun(recurrence: &grammar::Recurrence) -> bool {
+    std::iter::once(&recurrence.time_patterns)
+        .chain(recurrence.groups.iter().map(|group| &group.time_patterns))
+        .flatten()
+        .any(|time_pattern| {
+            matches!(time_pattern, grammar::TimePattern::Sun(_))
+        })
+}
+
+/// Whether the reminder has times relative to the sun
+pub fn uses_sun_times(reminder_pattern: &grammar::ReminderPattern) -> bool {
+    match reminder_pattern {
+        grammar::ReminderPattern::Recurrence(recurrence) => {
+            uses_sun(recurrence)
+        }
+        grammar::ReminderPattern::Countdown(_) => false,
+    }
+}
+
+impl TryFrom<&grammar::HoleyDate> for ExceptionDate {
+    type Error = ();
+
+    fn try_from(holey_date: &grammar::HoleyDate) -> Result<Self, ()> {
+        let date = Self {
+            year: holey_date.year,
+            month: holey_date.month.ok_or(())?,
+            day: holey_date.day.ok_or(())?,
+        };
+        // 29.02 is a valid yearly exception
+        NaiveDate::from_ymd_opt(
+            date.year.unwrap_or(2000),
+            date.month,
+            date.day,
+        )
+        .ok_or(())?;
+        Ok(date)
+    }
+}
+
+impl ExceptionDate {
+    fn key(&self) -> (Option<i32>, u32, u32) {
+        (self.year, self.month, self.day)
+    }
+
+    fn date_key(&self, date: NaiveDate) -> (Option<i32>, u32, u32) {
+        (self.year.map(|_| date.year()), date.month(), date.day())
+    }
+}
+
+impl TryFrom<grammar::Exception> for Exception {
+    type Error = ();
+
+    fn try_from(exception: grammar::Exception) -> Result<Self, ()> {
+        match exception {
+            grammar::Exception::Weekdays(weekdays) => {
+                Ok(Self::Weekdays(weekdays.into()))
+            }
+            grammar::Exception::Date(date) => {
+                let date = ExceptionDate::try_from(&date)?;
+                Ok(Self::Dates(date, date))
+            }
+            grammar::Exception::DateRange(from, until) => {
+                let from = ExceptionDate::try_from(&from)?;
+                let mut until = ExceptionDate::try_from(&until)?;
+                // a range is either bound to specific years or recurs yearly
+                if from.year.is_none() {
+                    until.year = None;
+                } else if until.year.is_none() {
+                    until.year = from.year.map(|year| {
+                        if (until.month, until.day) < (from.month, from.day) {
+                            year + 1
+                        } else {
+                            year
+                        }
+                    });
+                }
+                if from.year.is_some() && until.key() < from.key() {
+                    return Err(());
+                }
+                Ok(Self::Dates(from, until))
+            }
+            grammar::Exception::Time(time) => {
+                Ok(Self::Time(Time::from(&time).ok_or(())?))
+            }
+        }
+    }
+}
+
+impl Exception {
+    fn excludes(&self, time: NaiveDateTime) -> bool {
+        match *self {
+            Self::Weekdays(weekdays) => {
+                weekdays.bits() & (1 << time.weekday().num_days_from_monday())
+                    != 0
+            }
+            Self::Dates(from, until) => {
+                let (from_key, until_key) = (from.key(), until.key());
+                let key = from.date_key(time.date());
+                if from_key <= until_key {
+                    from_key <= key && key <= until_key
+                } else {
+                    // a yearly range crossing the new year
+                    from_key <= key || key <= until_key
+                }
+            }
+            Self::Time(excluded_time) => time.time() == excluded_time,
+        }
+    }
+}
+
+impl From<grammar::DateFilter> for DateFilter {
+    fn from(filter: grammar::DateFilter) -> Self {
+        match filter {
+            grammar::DateFilter::Months(months) => Self::Months(months),
+            grammar::DateFilter::Weekdays(weekdays) => {
+                Self::Weekdays(weekdays.into())
+            }
+        }
+    }
+}
+
+impl DateFilter {
+    fn includes(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Months(months) => months.contains(&date.month()),
+            Self::Weekdays(weekdays) => {
+                weekdays.bits() & (1 << date.weekday().num_days_from_monday())
+                    != 0
+            }
+        }
+    }
+}
+
+impl Recurrence {
+    pub(crate) fn from_with_tz(
+        mut recurrence: grammar::Recurrence,
+        tz: Tz,
+        location: Option<Location>,
+    ) -> Result<Self, ()> {
+        let tz = match recurrence.timezone {
+            Some(ref timezone) => Tz::from_grammar(timezone).ok_or(())?,
+            None => tz,
+        };
+        // the location is only kept if there are sun times to compute
+        let location = if uses_sun(&recurrence) {
+            Some(location.ok_or(())?)
+        } else {
+            None
+        };
+        // the dates of each group are resolved the same way as the first ones
+        let groups = std::mem::take(&mut recurrence.groups)
+            .into_iter()
+            .map(|group| {
+                let group = Self::from_with_tz(
+                    grammar::Recurrence {
+                        offset: recurrence.offset,
+                        dates_patterns: group.dates_patterns,
+                        time_patterns: group.time_patterns,
+                        ..Default::default()
+                    },
+                    tz,
+                    location,
+                )?;
+                Ok(RecurrenceGroup {
+                    dates_patterns: group.dates_patterns,
+                    time_patterns: group.time_patterns,
+                })
+            })
+            .collect::<Result<Vec<_>, ()>>()?;
+        let now = tz.to_local(&now_time());
+        // a delayed recurrence starts on the day the delay ends
+        let lower_bound = match recurrence.offset {
+            Some(offset) => date::add_date_interval(now.date(), &offset.into())
+                .ok_or(())?
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            None => now,
+        };
+        let is_relative = matches!(
+            recurrence.dates_patterns.first(),
+            grammar::DatePattern::Relative(_)
+        );
+        // relative dates are resolved against the current date
+        // and then treated as fully specified date points
+        let resolved_dates_patterns =
+            recurrence.dates_patterns.map(|pattern| match pattern {
+                grammar::DatePattern::Relative(ref relative_date) => {
+                    let date = resolve_relative_date(
+                        relative_date,
+                        lower_bound.date(),
+                    );
+                    grammar::DatePattern::Point(grammar::HoleyDate {
+                        year: Some(date.year()),
+                        month: Some(date.month()),
+                        day: Some(date.day()),
+                    })
+                }
+                pattern => pattern,
+            });
+        let first_time = match recurrence.time_patterns.first() {
+            Some(time_pattern) => match time_pattern {
+                grammar::TimePattern::Point(time) => {
+                    Time::from(time).ok_or(())?
+                }
+                grammar::TimePattern::Range(range) => range
+                    .from
+                    .as_ref()
+                    .and_then(Time::from)
+                    .unwrap_or(NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
+                grammar::TimePattern::Sun(sun_time) => SunTime::from(*sun_time)
+                    .time_on(lower_bound.date(), &location.ok_or(())?, tz)
+                    .map(|time| time.time())
+                    .unwrap_or(NaiveTime::MIN),
+            },
+            None => lower_bound.time(),
+        };
+        let first_date = match resolved_dates_patterns.first() {
+            grammar::DatePattern::Point(date) => date,
+            grammar::DatePattern::Range(range) => &range.from,
+            grammar::DatePattern::Relative(_) => unreachable!(),
+        };
+        let has_divisor = match resolved_dates_patterns.first() {
+            grammar::DatePattern::Point(_) => false,
+            grammar::DatePattern::Range(_) => true,
+            grammar::DatePattern::Relative(_) => unreachable!(),
+        };
+        let has_time_divisor = recurrence
+            .time_patterns
+            .iter()
+            .filter(|time_pattern| match time_pattern {
+                grammar::TimePattern::Point(_)
+                | grammar::TimePattern::Sun(_) => false,
+                grammar::TimePattern::Range(_) => true,
+            })
+            .count()
+            > 0;
+        let init_time = fill_date_holes(first_date, lower_bound.date())
+            .map(|date| date.and_time(first_time))
+            .ok_or(())?;
+        if is_relative && init_time < lower_bound && !has_time_divisor {
+            return Err(());
+        }
+        let init_time =
+            if init_time < lower_bound && !has_divisor && !has_time_divisor {
+                if first_date.day.is_none() {
+                    init_time + Duration::days(1)
+                } else if first_date.month.is_none() {
+                    shift_months(init_time, 1)
+                } else {
+                    shift_years(init_time, 1)
+                }
+            } else {
+                init_time
+            };
+        assert!(has_divisor || has_time_divisor || init_time >= lower_bound);
+        let mut cur_lower_bound = init_time.date();
+        let mut dates_patterns = vec![];
+        for pattern in resolved_dates_patterns {
+            match pattern {
+                grammar::DatePattern::Point(holey_date) => {
+                    let date = fill_date_holes(&holey_date, cur_lower_bound)
+                        .ok_or(())?;
+                    dates_patterns.push(DatePattern::Point(date));
+                    cur_lower_bound = date;
+                }
+                grammar::DatePattern::Range(grammar::DateRange {
+                    from,
+                    until,
+                    date_divisor,
+                }) => {
+                    let date_from =
+                        fill_date_holes(&from, cur_lower_bound).ok_or(())?;
+                    cur_lower_bound = date_from;
+                    let date_until = until.and_then(|until| {
+                        let date = fill_date_holes(&until, cur_lower_bound)?;
+                        cur_lower_bound = date;
+                        Some(date)
+                    });
+                    dates_patterns.push(DatePattern::Range(DateRange {
+                        from: date_from,
+                        until: date_until,
+                        date_divisor: date_divisor.into(),
+                    }));
+                }
+                grammar::DatePattern::Relative(_) => unreachable!(),
+            }
+        }
+        let time_patterns = recurrence
+            .time_patterns
+            .into_iter()
+            .map(TimePattern::from)
+            .collect::<Option<Vec<_>>>()
+            .ok_or(())?;
+        let exceptions = recurrence
+            .exceptions
+            .into_iter()
+            .map(Exception::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let filters = recurrence
+            .filters
+            .into_iter()
+            .map(DateFilter::from)
+            .collect();
+        let (remaining, end) = match recurrence.limit {
+            Some(grammar::RecurrenceLimit::Count(count)) => (Some(count), None),
+            Some(grammar::RecurrenceLimit::Duration(duration)) => {
+                let end = date::add_interval(lower_bound, &duration.into())
+                    .ok_or(())?;
+                let end = tz.local_to_utc(&end, DstPolicy::default());
+                (None, Some(end.ok_or(())?))
+            }
+            None => (None, None),
+        };
+        Ok(Self {
+            dates_patterns,
+            time_patterns,
+            exceptions,
+            filters,
+            timezone: tz,
+            remaining,
+            end,
+            groups,
+        })
+    }
+
+    pub(crate) fn next(
+        &self,
+        cur: NaiveDateTime,
+        calendar: Calendar,
+    ) -> Option<NaiveDateTime> {
+        let next = self.next_included(cur, calendar)?;
+        self.end.is_none_or(|end| next <= end).then_some(next)
+    }
+
+    fn next_included(
+        &self,
+        cur: NaiveDateTime,
+        calendar: Calendar,
+    ) -> Option<NaiveDateTime> {
+        let (mut next, mut date) = self.next_unfiltered(cur, calendar)?;
+        // give up if everything in sight is excluded
+        for _ in 0..MAX_SKIPPED_OCCURRENCES {
+            let local = self.timezone.to_local(&next);
+            let filtered_out =
+                !self.filters.iter().all(|filter| filter.includes(date));
+            // an occurrence is excluded by the date it's attributed to
+            let exception = self.exceptions.iter().find(|exception| {
+                exception.excludes(date.and_time(local.time()))
+            });
+            if !filtered_out && exception.is_none() {
+                return Some(next);
+            }
+            // skip the rest of an excluded day at once
+            if filtered_out || !matches!(exception, Some(Exception::Time(_))) {
+                let end_of_day = date.and_hms_opt(23, 59, 59).unwrap();
+                if end_of_day > local {
+                    next = self
+                        .timezone
+                        .local_to_utc(&end_of_day, calendar.dst)
+                        .unwrap_or(next);
+                }
+            }
+            (next, date) = self.next_unfiltered(next, calendar)?;
+        }
+        None
+    }
+
+    /// Date patterns along with their times, the first group included
+    fn groups(&self) -> impl Iterator<Item = (&[DatePattern], &[TimePattern])> {
+        std::iter::once((&self.dates_patterns[..], &self.time_patterns[..]))
+            .chain(self.groups.iter().map(|group| {
+                (&group.dates_patterns[..], &group.time_patterns[..])
+            }))
+    }
+
+    /// Find the next occurrence after `cur` along with the date
+    /// it's attributed to
+    fn next_unfiltered(
+        &self,
+        cur: NaiveDateTime,
+        calendar: Calendar,
+    ) -> Option<(NaiveDateTime, NaiveDate)> {
+        self.groups()
+            .filter_map(|(dates_patterns, time_patterns)| {
+                self.next_unfiltered_in(
+                    dates_patterns,
+                    time_patterns,
+                    cur,
+                    calendar,
+                )
+            })
+            .min()
+    }
+
+    fn next_unfiltered_in(
+        &self,
+        dates_patterns: &[DatePattern],
+        time_patterns: &[TimePattern],
+        cur: NaiveDateTime,
+        calendar: Calendar,
+    ) -> Option<(NaiveDateTime, NaiveDate)> {
+        let mut after = self.timezone.to_local(&cur);
+        // start from the previous date that may have a range
+        // crossing midnight
+        let mut date = nearest_date(
+            dates_patterns,
+            after.date().pred_opt()?,
+            calendar.holidays,
+        )?;
+        for _ in 0..MAX_SKIPPED_OCCURRENCES {
+            let Some(next) =
+                self.next_time_on(time_patterns, date, after, calendar)
+            else {
+                date = nearest_date(
+                    dates_patterns,
+                    date.succ_opt()?,
+                    calendar.holidays,
+                )?;
+                continue;
+            };
+            match self.timezone.local_to_utc(&next, calendar.dst) {
+                Some(next_utc) if next_utc > cur => {
+                    return Some((next_utc, date))
+                }
+                // the local time is skipped or repeated by a DST transition
+                _ => after = next,
+            }
+        }
+        None
+    }
+
+    /// Find the nearest local time after `cur` among the occurrences
+    /// attributed to `date`
+    fn next_time_on(
+        &self,
+        time_patterns: &[TimePattern],
+        date: NaiveDate,
+        cur: NaiveDateTime,
+        calendar: Calendar,
+    ) -> Option<NaiveDateTime> {
+        time_patterns
+            .iter()
+            .flat_map(|pattern| match pattern {
+                &TimePattern::Point(time) => Some(date.and_time(time)),
+                TimePattern::Range(ref range) => range.next_time_on(date, cur),
+                TimePattern::Sun(ref sun_time) => sun_time.time_on(
+                    date,
+                    calendar.location.as_ref()?,
+                    self.timezone,
+                ),
+            })
+            .filter(|&time| time > cur)
+            .min()
+    }
+}
+
+/// Find the nearest date not earlier than `date`
+/// that matches any of the date patterns
+fn nearest_date(
+    dates_patterns: &[DatePattern],
+    date: NaiveDate,
+    holidays: Option<Country>,
+) -> Option<NaiveDate> {
+    dates_patterns
+        .iter()
+        .flat_map(|pattern| match pattern {
+            &DatePattern::Point(point) => (point >= date).then_some(point),
+            DatePattern::Range(ref range) => {
+                range.get_nearest_date(date, holidays)
+            }
+        })
+        .min()
+}
+
+impl TimeRange {
+    fn crosses_midnight(&self) -> bool {
+        matches!((self.from, self.until), (Some(from), Some(until)) if until < from)
+    }
+
+    /// Find the nearest local time after `cur` in the range starting
+    /// on `date`, the range crossing midnight ends on the next date
+    fn next_time_on(
+        &self,
+        date: NaiveDate,
+        cur: NaiveDateTime,
+    ) -> Option<NaiveDateTime> {
+        let from = date.and_time(self.from.unwrap_or(NaiveTime::MIN));
+        let until = match self.until {
+            Some(until) if self.crosses_midnight() => {
+                date.succ_opt()?.and_time(until)
+            }
+            Some(until) => date.and_time(until),
+            None => date.and_hms_opt(23, 59, 59)?,
+        };
+        if let Some(random) = self.random {
+            return random
+                .times_on(date, from, until)
+                .into_iter()
+                .find(|&time| time > cur);
+        }
+        let step = Duration::from(self.interval).num_seconds();
+        let next = if from > cur {
+            from
+        } else {
+            from + Duration::seconds(
+                ((cur - from).num_seconds() / step + 1) * step,
+            )
+        };
+        (next <= until).then_some(next)
+    }
+}
+
+/// A step of the splitmix64 generator, which unlike the `rand` ones
+/// is guaranteed to stay the same
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A seed of the reminder being created, different even for the
+/// reminders created in the same second
+fn new_seed() -> u64 {
+    static CREATED: AtomicU64 = AtomicU64::new(0);
+    let now = now_time().and_utc();
+    let mut state = (now.timestamp() as u64)
+        ^ u64::from(now.timestamp_subsec_nanos()).rotate_left(32)
+        ^ splitmix64(&mut CREATED.fetch_add(1, Ordering::Relaxed));
+    splitmix64(&mut state)
+}
+
+impl RandomTimes {
+    /// Sorted distinct random times between `from` and `until` with
+    /// a minute precision, the same for the same date
+    fn times_on(
+        &self,
+        date: NaiveDate,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Vec<NaiveDateTime> {
+        let minutes = (until - from).num_minutes().max(0) as u64 + 1;
+        let mut state =
+            self.seed ^ splitmix64(&mut (date.num_days_from_ce() as u64));
+        let count = u64::from(self.count).min(minutes) as usize;
+        let mut times = Vec::with_capacity(count);
+        while times.len() < count {
+            let offset = splitmix64(&mut state) % minutes;
+            let time = from + Duration::minutes(offset as i64);
+            if !times.contains(&time) {
+                times.push(time);
+            }
+        }
+        times.sort();
+        times
+    }
+}
+
+impl Countdown {
+    /// The nearest time after `cur` without counting off its duration
+    fn next_after(
+        &self,
+        cur: NaiveDateTime,
+        dst: DstPolicy,
+    ) -> Option<NaiveDateTime> {
+        let start = self.timezone.to_local(&self.time_from);
+        self.durations
+            .iter()
+            .filter_map(|duration| {
+                self.timezone
+                    .local_to_utc(&date::add_interval(start, duration)?, dst)
+            })
+            .filter(|&time| time > cur)
+            .min()
+    }
+
+    pub(crate) fn next(&mut self, dst: DstPolicy) -> Option<NaiveDateTime> {
+        let start = self.timezone.to_local(&self.time_from);
+        // the durations that overflow never come
+        let (duration, next_time) = self
+            .durations
+            .iter()
+            .filter_map(|&duration| {
+                Some((duration, date::add_interval(start, &duration)?))
+            })
+            .min_by_key(|&(_, time)| time)?;
+        self.durations.retain(|&x| x != duration);
+
+        self.timezone.local_to_utc(&next_time, dst)
+    }
+}
+
+impl Countdown {
+    fn from_with_tz(countdown: grammar::Countdown, tz: Tz) -> Self {
+        Self {
+            time_from: now_time(),
+            durations: countdown
+                .durations
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            timezone: tz,
+        }
+    }
+}
+
+impl Pattern {
+    /// Convert the parsed pattern, the location is required for sun times
+    pub fn from_with_tz(
+        reminder_pattern: grammar::ReminderPattern,
+        tz: chrono_tz::Tz,
+        location: Option<Location>,
+    ) -> Option<Self> {
+        let tz = Tz::Named(tz);
+        match reminder_pattern {
+            grammar::ReminderPattern::Recurrence(recurrence) => {
+                Recurrence::from_with_tz(recurrence, tz, location)
+                    .ok()
+                    .map(Self::Recurrence)
+            }
+            grammar::ReminderPattern::Countdown(countdown) => {
+                Some(Self::Countdown(Countdown::from_with_tz(countdown, tz)))
+            }
+        }
+    }
+
+    pub fn next(
+        &mut self,
+        cur: NaiveDateTime,
+        calendar: Calendar,
+    ) -> Option<NaiveDateTime> {
+        match self {
+            Self::Recurrence(recurrence) => recurrence.next(cur, calendar),
+            Self::Countdown(countdown) => countdown.next(calendar.dst),
+        }
+    }
+
+    /// Iterate over the occurrences after `from` without changing
+    /// the pattern, up to the number of the occurrences left
+    pub fn occurrences(
+        &self,
+        from: NaiveDateTime,
+        calendar: Calendar,
+    ) -> Occurrences<'_> {
+        let remaining = match self {
+            Self::Recurrence(recurrence) => recurrence.remaining,
+            Self::Countdown(_) => None,
+        };
+        Occurrences {
+            pattern: self,
+            calendar,
+            cur: from,
+            remaining,
+        }
+    }
+
+    /// Occurrences after `from` up to `until` inclusive
+    pub fn between(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+        calendar: Calendar,
+    ) -> impl Iterator<Item = NaiveDateTime> + '_ {
+        self.occurrences(from, calendar)
+            .take_while(move |&time| time <= until)
+    }
+
+    /// Count off at most `limit` occurrences after `from` up to `until`
+    /// that were missed, returning their times
+    pub fn take_missed(
+        &mut self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+        limit: usize,
+        calendar: Calendar,
+    ) -> Vec<NaiveDateTime> {
+        let missed: Vec<_> =
+            self.between(from, until, calendar).take(limit).collect();
+        match self {
+            Self::Recurrence(recurrence) => {
+                if let Some(remaining) = recurrence.remaining.as_mut() {
+                    *remaining = remaining.saturating_sub(missed.len() as u32);
+                }
+            }
+            Self::Countdown(countdown) => {
+                for _ in &missed {
+                    countdown.next(calendar.dst);
+                }
+            }
+        }
+        missed
+    }
+
+    /// Whether the occurrence limit hasn't run out yet
+    pub fn has_remaining(&self) -> bool {
+        !matches!(
+            self,
+            Self::Recurrence(Recurrence {
+                remaining: Some(0),
+                ..
+            })
+        )
+    }
+
+    /// Count off a sent occurrence, returning whether there are any left
+    pub fn count_occurrence(&mut self) -> bool {
+        match self {
+            Self::Recurrence(Recurrence {
+                remaining: Some(remaining),
+                ..
+            }) => {
+                *remaining = remaining.saturating_sub(1);
+                *remaining > 0
+            }
+            _ => true,
+        }
+    }
+
+    pub fn timezone(&self) -> Tz {
+        match self {
+            Self::Recurrence(recurrence) => recurrence.timezone,
+            Self::Countdown(countdown) => countdown.timezone,
+        }
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Recurrence(recurrence) => write!(f, "{recurrence}"),
+            Self::Countdown(countdown) => write!(f, "{countdown}"),
+        }
+    }
+}
+
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let now = self.timezone.to_local(&now_time());
+        if self.time_patterns.len() == 1
+            && self.dates_patterns.len() == 1
+            && matches!(self.time_patterns[0], TimePattern::Point(_))
+            && matches!(self.dates_patterns[0], DatePattern::Point(_))
+            && self.exceptions.is_empty()
+            && self.filters.is_empty()
+            && self.groups.is_empty()
+        {
+            return Ok(());
+        }
+        for (i, (dates_patterns, time_patterns)) in self.groups().enumerate() {
+            if i != 0 {
+                write!(f, "; ")?;
+            }
+            let mut nonempty = false;
+            for (i, dates_pattern) in dates_patterns.iter().enumerate() {
+                if i != 0 {
+                    write!(f, ",")?;
+                }
+                nonempty |= dates_pattern.relfmt(f, &now)?;
+            }
+            if nonempty {
+                write!(f, " ")?;
+            }
+            for (i, time_pattern) in time_patterns.iter().enumerate() {
+                if i != 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{time_pattern}")?;
+            }
+        }
+        for filter in &self.filters {
+            write!(f, " {filter}")?;
+        }
+        for (i, exception) in self.exceptions.iter().enumerate() {
+            if i == 0 {
+                write!(f, " except ")?;
+            } else {
+                write!(f, ",")?;
+            }
+            write!(f, "{exception}")?;
+        }
+        if let Some(remaining) = self.remaining {
+            write!(f, " ({remaining} left)")?;
+        }
+        if let Some(end) = self.end {
+            let end = self.timezone.to_local(&end);
+            write!(f, " (until ")?;
+            if end.date().relfmt(f, &now)? {
+                write!(f, " ")?;
+            }
+            write!(f, "{})", end.format("%H:%M"))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Exception {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Weekdays(weekdays) => weekdays.fmt(f),
+            Self::Dates(from, until) if from == until => from.fmt(f),
+            Self::Dates(from, until) => write!(f, "{from}—{until}"),
+            Self::Time(time) => {
+                write!(f, "{:02}:{:02}", time.hour(), time.minute())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for DateFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Months(months) => {
+                write!(f, "in ")?;
+                for (i, &month) in months.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    let name = NaiveDate::from_ymd_opt(2000, month, 1)
+                        .ok_or(std::fmt::Error)?
+                        .format("%b");
+                    write!(f, "{name}")?;
+                }
+                Ok(())
+            }
+            Self::Weekdays(weekdays) => write!(f, "if {weekdays}"),
+        }
+    }
+}
+
+impl std::fmt::Display for ExceptionDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}.{:02}", self.day, self.month)?;
+        if let Some(year) = self.year {
+            write!(f, ".{year}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Countdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, interval) in self.durations.iter().enumerate() {
+            if i != 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{interval}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TimePattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Point(time) => {
+                write!(f, "{:02}:{:02}", time.hour(), time.minute())
+            }
+            Self::Range(range) => range.fmt(f),
+            Self::Sun(sun_time) => sun_time.fmt(f),
+        }
+    }
+}
+
+impl std::fmt::Display for SunTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.event {
+            SunEvent::Sunrise => write!(f, "sunrise")?,
+            SunEvent::Sunset => write!(f, "sunset")?,
+        }
+        if self.offset != 0 {
+            let seconds = self.offset.unsigned_abs() as u32;
+            let offset = TimeInterval {
+                hours: seconds / 3600,
+                minutes: seconds / 60 % 60,
+                seconds: seconds % 60,
+            };
+            let sign = if self.offset < 0 { '-' } else { '+' };
+            write!(f, "{sign}{offset}")?;
+        }
+        Ok(())
+    }
+}
+
+impl DateDisplay for DatePattern {
+    fn relfmt<D: Datelike>(
+        &self,
+        f: &mut Formatter<'_>,
+        now: &D,
+    ) -> Result<bool, std::fmt::Error> {
+        match self {
+            Self::Point(date) => date.relfmt(f, now),
+            Self::Range(range) => range.relfmt(f, now),
+        }
+    }
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<NaiveDateTime> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let next = match self.pattern {
+            Pattern::Recurrence(recurrence) => {
+                recurrence.next(self.cur, self.calendar)
+            }
+            Pattern::Countdown(countdown) => {
+                countdown.next_after(self.cur, self.calendar.dst)
+            }
+        }?;
+        self.cur = next;
+        self.remaining = self.remaining.map(|remaining| remaining - 1);
+        Some(next)
+    }
+}
+
+impl std::fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(random) = self.random {
+            write!(f, "randomly ")?;
+            if random.count > 1 {
+                write!(f, "{} times ", random.count)?;
+            }
+        }
+        if let Some(from) = self.from {
+            write!(f, "{:02}:{:02}", from.hour(), from.minute())?;
+        }
+        write!(f, "—")?;
+        if let Some(until) = self.until {
+            write!(f, "{:02}:{:02}", until.hour(), until.minute())?;
+        }
+        if self.random.is_none() {
+            write!(f, "/")?;
+            self.interval.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl DateDisplay for DateRange {
+    fn relfmt<D: Datelike>(
+        &self,
+        f: &mut Formatter<'_>,
+        now: &D,
+    ) -> Result<bool, std::fmt::Error> {
+        if self.from.year() > now.year()
+            || self.from.year() == now.year()
+                && (self.from.month() > now.month()
+                    || self.from.month() == now.month()
+                        && self.from.day() >= now.day())
+        {
+            self.from.relfmt(f, now)?;
+        }
+        write!(f, "—")?;
+        if let Some(until) = self.until {
+            if self.from != until {
+                until.relfmt(f, now)?;
+            }
+        }
+        if !matches!(
+            self.date_divisor,
+            DateDivisor::Interval(DateInterval {
+                years: 0,
+                months: 0,
+                weeks: 0,
+                days: 1,
+            })
+        ) {
+            write!(f, "/{}", self.date_divisor)?;
+        }
+        Ok(true)
+    }
+}
+
+impl DateDisplay for NaiveDate {
+    fn relfmt<D: Datelike>(
+        &self,
+        f: &mut Formatter<'_>,
+        now: &D,
+    ) -> Result<bool, std::fmt::Error> {
+        let same_year = self.year() == now.year();
+        let same_month = same_year && self.month() == now.month();
+        let same_day = same_month && self.day() == now.day();
+        if same_day {
+            Ok(false)
+        } else {
+            if same_year {
+                write!(f, "{}", self.format("%d.%m"))?;
+            } else {
+                write!(f, "{}", self.format("%d.%m.%y"))?;
+            }
+            Ok(true)
+        }
+    }
+}
+
+impl std::fmt::Display for DateDivisor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            DateDivisor::Weekdays(weekdays) => weekdays.fmt(f),
+            DateDivisor::Interval(interval) => interval.fmt(f),
+            DateDivisor::OrdinalWeekday(ordinal_weekday) => {
+                ordinal_weekday.fmt(f)
+            }
+            DateDivisor::MonthEnd(0) => write!(f, "last day"),
+            DateDivisor::MonthEnd(days_before) => {
+                write!(f, "{days_before}d before end")
+            }
+            DateDivisor::Workdays => write!(f, "workdays"),
+        }
+    }
+}
+
+impl std::fmt::Display for OrdinalWeekday {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.ordinal {
+            -1 => write!(f, "last")?,
+            1 => write!(f, "1st")?,
+            2 => write!(f, "2nd")?,
+            3 => write!(f, "3rd")?,
+            n => write!(f, "{n}th")?,
+        }
+        write!(f, " {}", self.weekday)
+    }
+}
+
+impl std::fmt::Display for Weekdays {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+            .iter()
+            .enumerate()
+            .filter_map(|(i, w)| {
+                if self.bits() & (1 << i) != 0 {
+                    Some(w)
+                } else {
+                    None
+                }
+            });
+        for (i, weekday) in weekdays.enumerate() {
+            if i != 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{weekday}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TimeInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.hours != 0 {
+            write!(f, "{}h", self.hours)?;
+        }
+        if self.minutes != 0 {
+            write!(f, "{}m", self.minutes)?;
+        }
+        if self.seconds != 0 {
+            write!(f, "{}s", self.seconds)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for DateInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.years != 0 {
+            write!(f, "{}y", self.years)?;
+        }
+        if self.months != 0 {
+            write!(f, "{}mo", self.months)?;
+        }
+        if self.weeks != 0 {
+            write!(f, "{}w", self.weeks)?;
+        }
+        if self.days != 0 {
+            write!(f, "{}d", self.days)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.years != 0 {
+            write!(f, "{}y", self.years)?;
+        }
+        if self.months != 0 {
+            write!(f, "{}mo", self.months)?;
+        }
+        if self.weeks != 0 {
+            write!(f, "{}w", self.weeks)?;
+        }
+        if self.days != 0 {
+            write!(f, "{}d", self.days)?;
+        }
+        if self.hours != 0 {
+            write!(f, "{}h", self.hours)?;
+        }
+        if self.minutes != 0 {
+            write!(f, "{}m", self.minutes)?;
+        }
+        if self.seconds != 0 {
+            write!(f, "{}s", self.seconds)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::clock::TEST_TIMESTAMP;
+    use lazy_static::lazy_static;
+    use proptest::prelude::*;
+    use proptest::sample::select;
+    use remindee_parser::parse_reminder;
+    use test_case::test_case;
+
+    lazy_static! {
+        static ref TEST_TZ: chrono_tz::Tz =
+            "Europe/Moscow".parse::<chrono_tz::Tz>().unwrap();
+        static ref TEST_TIME: DateTime<chrono_tz::Tz> =
+            TEST_TZ.with_ymd_and_hms(2007, 2, 2, 12, 30, 30).unwrap();
+    }
+
+    const TEST_LOCATION: Location = Location {
+        latitude: 55.75,
+        longitude: 37.62,
+    };
+
+    fn test_calendar() -> Calendar {
+        Calendar {
+            location: Some(TEST_LOCATION),
+            ..Calendar::default()
+        }
+    }
+
+    fn get_all_times(pattern: Pattern) -> impl Iterator<Item = NaiveDateTime> {
+        get_all_times_in(pattern, test_calendar())
+    }
+
+    fn get_all_times_in(
+        mut pattern: Pattern,
+        calendar: Calendar,
+    ) -> impl Iterator<Item = NaiveDateTime> {
+        let cur = now_time();
+        std::iter::successors(Some(cur), move |&cur| {
+            pattern.next(cur, calendar)
+        })
+        .skip(1)
+        .map(|x| TEST_TZ.from_utc_datetime(&x).naive_local())
+    }
+
+    fn tz(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        min: u32,
+        sec: u32,
+    ) -> NaiveDateTime {
+        TEST_TZ
+            .with_ymd_and_hms(year, month, day, hour, min, sec)
+            .unwrap()
+            .naive_local()
+    }
+
+    #[test]
+    #[serial]
+    fn test_countdown() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "1w1h2m3s countdown";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("countdown".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![tz(2007, 2, 9, 13, 32, 33)]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_multiple_countdown() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "1w1h2m3s,2w1h20m7s countdown";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("countdown".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![tz(2007, 2, 9, 13, 32, 33), tz(2007, 2, 16, 13, 50, 37)]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_periodic() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "- 11-18/1h periodic";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("periodic".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).take(15).collect::<Vec<_>>(),
+            vec![
+                tz(2007, 2, 2, 13, 0, 0),
+                tz(2007, 2, 2, 14, 0, 0),
+                tz(2007, 2, 2, 15, 0, 0),
+                tz(2007, 2, 2, 16, 0, 0),
+                tz(2007, 2, 2, 17, 0, 0),
+                tz(2007, 2, 2, 18, 0, 0),
+                tz(2007, 2, 3, 11, 0, 0),
+                tz(2007, 2, 3, 12, 0, 0),
+                tz(2007, 2, 3, 13, 0, 0),
+                tz(2007, 2, 3, 14, 0, 0),
+                tz(2007, 2, 3, 15, 0, 0),
+                tz(2007, 2, 3, 16, 0, 0),
+                tz(2007, 2, 3, 17, 0, 0),
+                tz(2007, 2, 3, 18, 0, 0),
+                tz(2007, 2, 4, 11, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_date_range() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "3-6/2d 13:37 date range";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("date range".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![tz(2007, 2, 3, 13, 37, 0), tz(2007, 2, 5, 13, 37, 0),]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_date_range_month_names() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "28 feb-mar 2 10:00 month names range";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("month names range".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![
+                tz(2007, 2, 28, 10, 0, 0),
+                tz(2007, 3, 1, 10, 0, 0),
+                tz(2007, 3, 2, 10, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_date_format1() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "07.06.2025 13:37";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(parsed_rem.description.map(|x| x.0), None);
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![tz(2025, 6, 7, 13, 37, 0)]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_date_format2() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "2025/06/07 13:37 date format2";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("date format2".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![tz(2025, 6, 7, 13, 37, 0)]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_end_of_month_increment() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "12/31/1MONTH 13:37 end of month";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("end of month".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).take(16).collect::<Vec<_>>(),
+            vec![
+                tz(2007, 12, 31, 13, 37, 0),
+                tz(2008, 1, 31, 13, 37, 0),
+                tz(2008, 2, 29, 13, 37, 0),
+                tz(2008, 3, 29, 13, 37, 0),
+                tz(2008, 4, 29, 13, 37, 0),
+                tz(2008, 5, 29, 13, 37, 0),
+                tz(2008, 6, 29, 13, 37, 0),
+                tz(2008, 7, 29, 13, 37, 0),
+                tz(2008, 8, 29, 13, 37, 0),
+                tz(2008, 9, 29, 13, 37, 0),
+                tz(2008, 10, 29, 13, 37, 0),
+                tz(2008, 11, 29, 13, 37, 0),
+                tz(2008, 12, 29, 13, 37, 0),
+                tz(2009, 1, 29, 13, 37, 0),
+                tz(2009, 2, 28, 13, 37, 0),
+                tz(2009, 3, 28, 13, 37, 0),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_weekdays() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "/fri,mon 11:00 weekdays";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("weekdays".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).take(4).collect::<Vec<_>>(),
+            vec![
+                tz(2007, 2, 5, 11, 0, 0),
+                tz(2007, 2, 9, 11, 0, 0),
+                tz(2007, 2, 12, 11, 0, 0),
+                tz(2007, 2, 16, 11, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_weekdays_ranges() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "/fri-mon,wed 15:00:20 weekdays ranges";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("weekdays ranges".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).take(10).collect::<Vec<_>>(),
+            vec![
+                tz(2007, 2, 2, 15, 0, 20),
+                tz(2007, 2, 3, 15, 0, 20),
+                tz(2007, 2, 4, 15, 0, 20),
+                tz(2007, 2, 5, 15, 0, 20),
+                tz(2007, 2, 7, 15, 0, 20),
+                tz(2007, 2, 9, 15, 0, 20),
+                tz(2007, 2, 10, 15, 0, 20),
+                tz(2007, 2, 11, 15, 0, 20),
+                tz(2007, 2, 12, 15, 0, 20),
+                tz(2007, 2, 14, 15, 0, 20),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_description_trim() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "15:16     test    description   ";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("test    description".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![tz(2007, 2, 2, 15, 16, 0),]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_date_range_weekends() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "10-20/mon,fri-sun 11-12/1h date range weekends";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("date range weekends".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![
+                tz(2007, 2, 10, 11, 0, 0),
+                tz(2007, 2, 10, 12, 0, 0),
+                tz(2007, 2, 11, 11, 0, 0),
+                tz(2007, 2, 11, 12, 0, 0),
+                tz(2007, 2, 12, 11, 0, 0),
+                tz(2007, 2, 12, 12, 0, 0),
+                tz(2007, 2, 16, 11, 0, 0),
+                tz(2007, 2, 16, 12, 0, 0),
+                tz(2007, 2, 17, 11, 0, 0),
+                tz(2007, 2, 17, 12, 0, 0),
+                tz(2007, 2, 18, 11, 0, 0),
+                tz(2007, 2, 18, 12, 0, 0),
+                tz(2007, 2, 19, 11, 0, 0),
+                tz(2007, 2, 19, 12, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_date_range_over_year() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "12/16-3/16/1m 18:15 date range over year";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("date range over year".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).collect::<Vec<_>>(),
+            vec![
+                tz(2007, 12, 16, 18, 15, 0),
+                tz(2008, 1, 16, 18, 15, 0),
+                tz(2008, 2, 16, 18, 15, 0),
+                tz(2008, 3, 16, 18, 15, 0),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_ordinal_weekdays() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "every first monday 10:00 review",
+                vec![
+                    tz(2007, 2, 5, 10, 0, 0),
+                    tz(2007, 3, 5, 10, 0, 0),
+                    tz(2007, 4, 2, 10, 0, 0),
+                    tz(2007, 5, 7, 10, 0, 0),
+                ],
+            ),
+            (
+                "on the 2nd tue 9:00 sprint review",
+                vec![
+                    tz(2007, 2, 13, 9, 0, 0),
+                    tz(2007, 3, 13, 9, 0, 0),
+                    tz(2007, 4, 10, 9, 0, 0),
+                    tz(2007, 5, 8, 9, 0, 0),
+                ],
+            ),
+            (
+                "last friday of every month 17:00 payroll",
+                vec![
+                    tz(2007, 2, 23, 17, 0, 0),
+                    tz(2007, 3, 30, 17, 0, 0),
+                    tz(2007, 4, 27, 17, 0, 0),
+                    tz(2007, 5, 25, 17, 0, 0),
+                ],
+            ),
+            (
+                "every 5th fri 12:00 rare",
+                vec![
+                    tz(2007, 3, 30, 12, 0, 0),
+                    tz(2007, 6, 29, 12, 0, 0),
+                    tz(2007, 8, 31, 12, 0, 0),
+                    tz(2007, 11, 30, 12, 0, 0),
+                ],
+            ),
+            (
+                "-31.03/first mon 8:00 bounded",
+                vec![tz(2007, 2, 5, 8, 0, 0), tz(2007, 3, 5, 8, 0, 0)],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(4).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_workdays_and_weekends() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "every workday 10:00 standup",
+                vec![
+                    tz(2007, 2, 5, 10, 0, 0),
+                    tz(2007, 2, 6, 10, 0, 0),
+                    tz(2007, 2, 7, 10, 0, 0),
+                    tz(2007, 2, 8, 10, 0, 0),
+                ],
+            ),
+            (
+                "on weekends 11:00 brunch",
+                vec![
+                    tz(2007, 2, 3, 11, 0, 0),
+                    tz(2007, 2, 4, 11, 0, 0),
+                    tz(2007, 2, 10, 11, 0, 0),
+                    tz(2007, 2, 11, 11, 0, 0),
+                ],
+            ),
+            (
+                "- 13:00 except weekends lunch",
+                vec![
+                    tz(2007, 2, 2, 13, 0, 0),
+                    tz(2007, 2, 5, 13, 0, 0),
+                    tz(2007, 2, 6, 13, 0, 0),
+                    tz(2007, 2, 7, 13, 0, 0),
+                ],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(4).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_workdays_skip_holidays() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("-28.02/workdays 9:00 standup")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        let calendar = Calendar {
+            holidays: Some(Country::Russia),
+            ..Calendar::default()
+        };
+        let times = get_all_times_in(pattern, calendar).collect::<Vec<_>>();
+        assert_eq!(times.len(), 17);
+        assert!(!times.contains(&tz(2007, 2, 23, 9, 0, 0)));
+        assert_eq!(times.last(), Some(&tz(2007, 2, 28, 9, 0, 0)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_ordinal_weekdays_display_round_trip() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("last fri of the month 17:00 payroll")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        let displayed = pattern.to_string();
+        assert_eq!(displayed, "—/last Fri 17:00");
+        let reparsed = parse_reminder(&displayed).unwrap().pattern.unwrap();
+        let reparsed = Pattern::from_with_tz(reparsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(reparsed).take(3).collect::<Vec<_>>(),
+            get_all_times(pattern).take(3).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_month_end() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "last day of every month 10:00 rent",
+                vec![
+                    tz(2007, 2, 28, 10, 0, 0),
+                    tz(2007, 3, 31, 10, 0, 0),
+                    tz(2007, 4, 30, 10, 0, 0),
+                    tz(2007, 5, 31, 10, 0, 0),
+                ],
+            ),
+            (
+                "3 days before the end of the month 9:00 invoices",
+                vec![
+                    tz(2007, 2, 25, 9, 0, 0),
+                    tz(2007, 3, 28, 9, 0, 0),
+                    tz(2007, 4, 27, 9, 0, 0),
+                    tz(2007, 5, 28, 9, 0, 0),
+                ],
+            ),
+            (
+                "1.01.2008- on the last day 12:00 leap year",
+                vec![
+                    tz(2008, 1, 31, 12, 0, 0),
+                    tz(2008, 2, 29, 12, 0, 0),
+                    tz(2008, 3, 31, 12, 0, 0),
+                    tz(2008, 4, 30, 12, 0, 0),
+                ],
+            ),
+            (
+                "-31.03/1d before month end 8:00 bounded",
+                vec![tz(2007, 2, 27, 8, 0, 0), tz(2007, 3, 30, 8, 0, 0)],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(4).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_recurrence_groups() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "on mon 10:00, wed 15:30 sync",
+                vec![
+                    tz(2007, 2, 5, 10, 0, 0),
+                    tz(2007, 2, 7, 15, 30, 0),
+                    tz(2007, 2, 12, 10, 0, 0),
+                    tz(2007, 2, 14, 15, 30, 0),
+                ],
+            ),
+            (
+                "on mon,fri 9:00; on sat 11:00,13:00 gym",
+                vec![
+                    tz(2007, 2, 3, 11, 0, 0),
+                    tz(2007, 2, 3, 13, 0, 0),
+                    tz(2007, 2, 5, 9, 0, 0),
+                    tz(2007, 2, 9, 9, 0, 0),
+                ],
+            ),
+            (
+                "on mon 10:00; 6.02 18:00 meeting",
+                vec![
+                    tz(2007, 2, 5, 10, 0, 0),
+                    tz(2007, 2, 6, 18, 0, 0),
+                    tz(2007, 2, 12, 10, 0, 0),
+                    tz(2007, 2, 19, 10, 0, 0),
+                ],
+            ),
+            (
+                "on mon 10:00, thu 16:00 except 8.02 standup",
+                vec![
+                    tz(2007, 2, 5, 10, 0, 0),
+                    tz(2007, 2, 12, 10, 0, 0),
+                    tz(2007, 2, 15, 16, 0, 0),
+                    tz(2007, 2, 19, 10, 0, 0),
+                ],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(4).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_recurrence_groups_display_round_trip() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("on mon 10:00, wed 15:30 sync")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        let displayed = pattern.to_string();
+        assert_eq!(displayed, "—/Mon 10:00; —/Wed 15:30");
+        let reparsed = parse_reminder(&displayed).unwrap().pattern.unwrap();
+        let repattern =
+            Pattern::from_with_tz(reparsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(repattern).take(4).collect::<Vec<_>>(),
+            get_all_times(pattern).take(4).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_time_ranges_crossing_midnight() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "22:00-02:00 every 1h night",
+                vec![
+                    tz(2007, 2, 2, 22, 0, 0),
+                    tz(2007, 2, 2, 23, 0, 0),
+                    tz(2007, 2, 3, 0, 0, 0),
+                    tz(2007, 2, 3, 1, 0, 0),
+                    tz(2007, 2, 3, 2, 0, 0),
+                ],
+            ),
+            (
+                "on fri 23:00-01:30 every 30m shift",
+                vec![
+                    tz(2007, 2, 2, 23, 0, 0),
+                    tz(2007, 2, 2, 23, 30, 0),
+                    tz(2007, 2, 3, 0, 0, 0),
+                    tz(2007, 2, 3, 0, 30, 0),
+                    tz(2007, 2, 3, 1, 0, 0),
+                    tz(2007, 2, 3, 1, 30, 0),
+                    tz(2007, 2, 9, 23, 0, 0),
+                ],
+            ),
+            (
+                "on sat 22:00-02:00 every 2h shift",
+                vec![
+                    tz(2007, 2, 3, 22, 0, 0),
+                    tz(2007, 2, 4, 0, 0, 0),
+                    tz(2007, 2, 4, 2, 0, 0),
+                    tz(2007, 2, 10, 22, 0, 0),
+                ],
+            ),
+            (
+                "on fri,sat 23:00-01:00 every 1h except sat shift",
+                vec![
+                    tz(2007, 2, 2, 23, 0, 0),
+                    tz(2007, 2, 3, 0, 0, 0),
+                    tz(2007, 2, 3, 1, 0, 0),
+                    tz(2007, 2, 9, 23, 0, 0),
+                ],
+            ),
+            (
+                "-/1d 23:00-01:00 every 1h except 3.02 shift",
+                vec![
+                    tz(2007, 2, 2, 23, 0, 0),
+                    tz(2007, 2, 3, 0, 0, 0),
+                    tz(2007, 2, 3, 1, 0, 0),
+                    tz(2007, 2, 4, 23, 0, 0),
+                ],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern)
+                    .take(expected.len())
+                    .collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_time_range_crossing_midnight_resumed() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("on fri 22:00-02:00 every 1h shift")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let mut pattern =
+            Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        let cur = TEST_TZ
+            .with_ymd_and_hms(2007, 2, 3, 0, 30, 0)
+            .unwrap()
+            .naive_utc();
+        assert_eq!(
+            pattern
+                .next(cur, Calendar::default())
+                .map(|time| TEST_TZ.from_utc_datetime(&time).naive_local()),
+            Some(tz(2007, 2, 3, 1, 0, 0))
+        );
+    }
+
+    #[test_case(
+        "on sat 23:00-04:00 every 1h shift",
+        (2007, 3, 24),
+        vec![(24, 23), (25, 0), (25, 1), (25, 3), (25, 4)] ;
+        "spring forward"
+    )]
+    #[test_case(
+        "on sat 23:00-04:00 every 1h shift",
+        (2007, 10, 27),
+        vec![(27, 23), (28, 0), (28, 1), (28, 2), (28, 3), (28, 4)] ;
+        "fall back"
+    )]
+    #[serial]
+    fn test_time_range_crossing_midnight_dst(
+        s: &str,
+        (year, month, day): (i32, u32, u32),
+        expected: Vec<(u32, u32)>,
+    ) {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let berlin = chrono_tz::Europe::Berlin;
+        let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+        let mut pattern = Pattern::from_with_tz(parsed, berlin, None).unwrap();
+        let start = berlin
+            .with_ymd_and_hms(year, month, day, 12, 0, 0)
+            .unwrap()
+            .naive_utc();
+        let times = std::iter::successors(Some(start), |&cur| {
+            pattern.next(cur, Calendar::default())
+        })
+        .skip(1)
+        .take(expected.len())
+        .map(|time| berlin.from_utc_datetime(&time))
+        .map(|time| (time.day(), time.hour()))
+        .collect::<Vec<_>>();
+        assert_eq!(times, expected);
+    }
+
+    #[test]
+    #[serial]
+    fn test_recurrence_limits() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "every 1d 9:00 x3 pills",
+                vec![
+                    tz(2007, 2, 3, 9, 0, 0),
+                    tz(2007, 2, 4, 9, 0, 0),
+                    tz(2007, 2, 5, 9, 0, 0),
+                ],
+            ),
+            (
+                "on mon,fri 10:00 two times gym",
+                vec![tz(2007, 2, 5, 10, 0, 0), tz(2007, 2, 9, 10, 0, 0)],
+            ),
+            (
+                "every 1h for 3 hours stretch",
+                vec![
+                    tz(2007, 2, 2, 13, 0, 0),
+                    tz(2007, 2, 2, 14, 0, 0),
+                    tz(2007, 2, 2, 15, 0, 0),
+                ],
+            ),
+            (
+                "every 1d 9:00,21:00 for 2 days pills",
+                vec![
+                    tz(2007, 2, 2, 21, 0, 0),
+                    tz(2007, 2, 3, 9, 0, 0),
+                    tz(2007, 2, 3, 21, 0, 0),
+                    tz(2007, 2, 4, 9, 0, 0),
+                ],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let mut pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            // mimic sending the reminders one by one
+            let mut times = vec![];
+            let mut cur = pattern.next(now_time(), Calendar::default());
+            while let Some(time) = cur {
+                times.push(TEST_TZ.from_utc_datetime(&time).naive_local());
+                cur = pattern
+                    .count_occurrence()
+                    .then(|| pattern.next(time, Calendar::default()))
+                    .flatten();
+            }
+            assert_eq!(times, expected, "{s}");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_recurrence_limits_display() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            ("every 1d 9:00 x10 pills", "— 09:00 (10 left)"),
+            ("every 1h for 3 hours stretch", "—/1h (until 15:30)"),
+            (
+                "every 1d 9:00 for a week pills",
+                "— 09:00 (until 09.02 12:30)",
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(pattern.to_string(), expected);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_date_filters() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "every mon in dec 10:00 meeting",
+                vec![
+                    tz(2007, 12, 3, 10, 0, 0),
+                    tz(2007, 12, 10, 10, 0, 0),
+                    tz(2007, 12, 17, 10, 0, 0),
+                    tz(2007, 12, 24, 10, 0, 0),
+                ],
+            ),
+            (
+                "friday the 13th 10:00 stay home",
+                vec![
+                    tz(2007, 4, 13, 10, 0, 0),
+                    tz(2007, 7, 13, 10, 0, 0),
+                    tz(2008, 6, 13, 10, 0, 0),
+                    tz(2009, 2, 13, 10, 0, 0),
+                ],
+            ),
+            (
+                "13/1m 10:00 if it's a friday stay home",
+                vec![
+                    tz(2007, 4, 13, 10, 0, 0),
+                    tz(2007, 7, 13, 10, 0, 0),
+                    tz(2008, 6, 13, 10, 0, 0),
+                    tz(2009, 2, 13, 10, 0, 0),
+                ],
+            ),
+            (
+                "every 1d in summer 8:00 water the plants",
+                vec![
+                    tz(2007, 6, 1, 8, 0, 0),
+                    tz(2007, 6, 2, 8, 0, 0),
+                    tz(2007, 6, 3, 8, 0, 0),
+                    tz(2007, 6, 4, 8, 0, 0),
+                ],
+            ),
+            (
+                "on weekends 9:00 in nov-feb except 10.02 ski",
+                vec![
+                    tz(2007, 2, 3, 9, 0, 0),
+                    tz(2007, 2, 4, 9, 0, 0),
+                    tz(2007, 2, 11, 9, 0, 0),
+                    tz(2007, 2, 17, 9, 0, 0),
+                ],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(4).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_date_filters_display_round_trip() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            ("every mon in dec 10:00 meeting", "—/Mon 10:00 in Dec"),
+            ("friday the 13th 10:00 stay home", "13.02—/1mo 10:00 if Fri"),
+            ("every 1d 8:00 in summer water", "— 08:00 in Jun,Jul,Aug"),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(pattern.to_string(), expected);
+            let reparsed = parse_reminder(&format!("{expected} x"))
+                .unwrap()
+                .pattern
+                .unwrap();
+            let repattern =
+                Pattern::from_with_tz(reparsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(repattern).take(4).collect::<Vec<_>>(),
+                get_all_times(pattern).take(4).collect::<Vec<_>>(),
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_sun_times() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let sun_times = |s: &str| {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, Some(TEST_LOCATION))
+                    .unwrap();
+            get_all_times(pattern).take(5).collect::<Vec<_>>()
+        };
+        let sunsets = sun_times("every 1d sunset lights");
+        let expected = (2..7)
+            .map(|day| {
+                let date = NaiveDate::from_ymd_opt(2007, 2, day).unwrap();
+                let sunset =
+                    TEST_LOCATION.sun_event(SunEvent::Sunset, date).unwrap();
+                TEST_TZ.from_utc_datetime(&sunset).naive_local()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(sunsets, expected);
+        // the days are getting longer
+        assert!(sunsets
+            .windows(2)
+            .all(|pair| pair[0].time() < pair[1].time()));
+        let before_sunsets =
+            sun_times("every 1d 30 minutes before sunset walk");
+        assert_eq!(
+            before_sunsets,
+            sunsets
+                .iter()
+                .map(|&time| time - Duration::minutes(30))
+                .collect::<Vec<_>>()
+        );
+        let sunrises = sun_times("on mon sunrise+1h run");
+        assert_eq!(
+            sunrises[0].date(),
+            NaiveDate::from_ymd_opt(2007, 2, 5).unwrap()
+        );
+        assert_eq!(
+            sunrises[1].date(),
+            NaiveDate::from_ymd_opt(2007, 2, 12).unwrap()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_sun_times_polar_night() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("1.12-20.01 sunrise x")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let tromso = Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+        let pattern =
+            Pattern::from_with_tz(parsed, *TEST_TZ, Some(tromso)).unwrap();
+        let calendar = Calendar {
+            location: Some(tromso),
+            ..Calendar::default()
+        };
+        // the sun doesn't rise for weeks, so the first sunrise is in January
+        let first = get_all_times_in(pattern, calendar).next().unwrap();
+        assert_eq!(first.date().month(), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_sun_times_follow_location() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("every 1d sunset lights")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern =
+            Pattern::from_with_tz(parsed, *TEST_TZ, Some(TEST_LOCATION))
+                .unwrap();
+        assert!(!serde_json::to_string(&pattern).unwrap().contains("loc"));
+        // the sunsets of the place the user has moved to
+        let tromso = Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+        let calendar = Calendar {
+            location: Some(tromso),
+            ..Calendar::default()
+        };
+        let date = NaiveDate::from_ymd_opt(2007, 2, 2).unwrap();
+        let sunset = tromso.sun_event(SunEvent::Sunset, date).unwrap();
+        assert_eq!(
+            get_all_times_in(pattern, calendar).next(),
+            Some(TEST_TZ.from_utc_datetime(&sunset).naive_local())
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_sun_times_need_location() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("sunset x").unwrap().pattern.unwrap();
+        assert!(Pattern::from_with_tz(parsed, *TEST_TZ, None).is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_sun_times_display_round_trip() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            ("every 1d sunset lights", "— sunset"),
+            ("every 1d 1h30m before sunrise x", "— sunrise-1h30m"),
+            ("on fri sunset + 15m x", "—/Fri sunset+15m"),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, Some(TEST_LOCATION))
+                    .unwrap();
+            assert_eq!(pattern.to_string(), expected);
+            let serialized = serde_json::to_string(&pattern).unwrap();
+            let deserialized: Pattern =
+                serde_json::from_str(&serialized).unwrap();
+            assert_eq!(deserialized.to_string(), expected);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_random_times() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "every 1d 3 times randomly between 10:00 and 18:00 ping";
+        let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        let serialized = serde_json::to_string(&pattern).unwrap();
+        let times: Vec<_> = get_all_times(pattern).take(30).collect();
+        let window = NaiveTime::from_hms_opt(10, 0, 0).unwrap()
+            ..=NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        assert!(times.iter().all(|time| window.contains(&time.time())));
+        // three times on every day after today's ones
+        let first_day = times.iter().position(|time| time.day() == 3).unwrap();
+        for day in times[first_day..].chunks_exact(3) {
+            assert!(day.iter().all(|time| time.date() == day[0].date()));
+        }
+        assert!(times[first_day..]
+            .chunks_exact(3)
+            .any(|day| day[0].time() != times[first_day].time()));
+
+        // the same times after a restart
+        let deserialized: Pattern = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(
+            get_all_times(deserialized).take(30).collect::<Vec<_>>(),
+            times
+        );
+
+        // and different ones for a reminder created at another time
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp() + 1;
+        let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_ne!(get_all_times(pattern).take(30).collect::<Vec<_>>(), times);
+
+        // and for another reminder created in the same second
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_ne!(get_all_times(pattern).take(30).collect::<Vec<_>>(), times);
+    }
+
+    #[test]
+    #[serial]
+    fn test_random_times_display_round_trip() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "every 1d randomly once between 10:00-18:00 ping",
+                "— randomly 10:00—18:00",
+            ),
+            (
+                "on mon twice randomly 9-21 x",
+                "—/Mon randomly 2 times 09:00—21:00",
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(pattern.to_string(), expected);
+            let reparsed = parse_reminder(&format!("{expected} x"))
+                .unwrap()
+                .pattern
+                .unwrap();
+            let pattern =
+                Pattern::from_with_tz(reparsed, *TEST_TZ, None).unwrap();
+            assert_eq!(pattern.to_string(), expected);
+        }
+    }
+
+    const PROPERTY_DATES: &[&str] = &[
+        "every 1d",
+        "on mon,thu",
+        "every 2nd tue",
+        "last day of every month",
+        "every workday",
+        "1.03-1.06/3d",
+        "friday the 13th",
+        "every 1d in dec",
+        "on sat 10:00, sun",
+    ];
+    const PROPERTY_TIMES: &[&str] = &[
+        "10:00",
+        "9:00,21:00",
+        "22:00-02:00 every 1h",
+        "randomly 3 times 10:00-18:00",
+        "sunset",
+        "2:30",
+        "every 7h",
+    ];
+    const PROPERTY_LIMITS: &[&str] = &["", "x5", "for 2 weeks"];
+    const MAX_MISSED: usize = 100;
+
+    fn property_pattern(s: &str) -> Pattern {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+        Pattern::from_with_tz(parsed, *TEST_TZ, Some(TEST_LOCATION)).unwrap()
+    }
+
+    fn check_occurrences(
+        pattern: &Pattern,
+        from: NaiveDateTime,
+    ) -> Result<(), TestCaseError> {
+        let times: Vec<_> = pattern
+            .occurrences(from, test_calendar())
+            .take(100)
+            .collect();
+        prop_assert!(times.first().is_none_or(|&time| time > from));
+        prop_assert!(
+            times.windows(2).all(|pair| pair[0] < pair[1]),
+            "{times:?}"
+        );
+        if let Some(&last) = times.last() {
+            prop_assert_eq!(
+                pattern
+                    .between(from, last, test_calendar())
+                    .collect::<Vec<_>>(),
+                times
+            );
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        #[serial]
+        fn test_occurrences_strictly_increasing(
+            dates in select(PROPERTY_DATES),
+            times in select(PROPERTY_TIMES),
+            limit in select(PROPERTY_LIMITS),
+            offset in 0..4 * 365 * 86400_i64,
+        ) {
+            let s = [dates, times, limit, "x"]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let pattern = property_pattern(&s);
+            check_occurrences(&pattern, now_time() + Duration::seconds(offset))?;
+        }
+
+        #[test]
+        #[serial]
+        fn test_countdown_occurrences_strictly_increasing(
+            durations in proptest::collection::vec(1..100_u32, 1..5),
+            offset in 0..100 * 3600_i64,
+        ) {
+            let s = durations
+                .iter()
+                .map(|hours| format!("{hours}h"))
+                .collect::<Vec<_>>()
+                .join(",");
+            let pattern = property_pattern(&format!("{s} x"));
+            check_occurrences(&pattern, now_time() + Duration::seconds(offset))?;
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_occurrences_keep_pattern() {
+        let mut pattern = property_pattern("every 1d 9:00 x3 pills");
+        let times: Vec<_> = pattern
+            .occurrences(now_time(), Calendar::default())
+            .collect();
+        assert_eq!(
+            times
+                .iter()
+                .map(|time| TEST_TZ.from_utc_datetime(time).naive_local())
+                .collect::<Vec<_>>(),
+            vec![
+                tz(2007, 2, 3, 9, 0, 0),
+                tz(2007, 2, 4, 9, 0, 0),
+                tz(2007, 2, 5, 9, 0, 0),
+            ]
+        );
+        assert_eq!(
+            pattern
+                .occurrences(now_time(), Calendar::default())
+                .collect::<Vec<_>>(),
+            times
+        );
+        assert_eq!(
+            pattern.next(now_time(), Calendar::default()),
+            Some(times[0])
+        );
+        assert_eq!(
+            pattern
+                .between(times[0], times[2], Calendar::default())
+                .collect::<Vec<_>>(),
+            times[1..]
+        );
+
+        let mut countdown = property_pattern("1h,30m,2h x");
+        let times: Vec<_> = countdown
+            .occurrences(now_time(), Calendar::default())
+            .collect();
+        assert_eq!(times.len(), 3);
+        assert_eq!(
+            countdown
+                .occurrences(now_time(), Calendar::default())
+                .collect::<Vec<_>>(),
+            times
+        );
+        assert_eq!(
+            countdown.next(now_time(), Calendar::default()),
+            Some(times[0])
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_take_missed() {
+        let mut pattern = property_pattern("every 1h x4 stretch");
+        let first = pattern.next(now_time(), Calendar::default()).unwrap();
+        assert!(pattern.count_occurrence());
+        let missed = pattern.take_missed(
+            first,
+            first + Duration::minutes(150),
+            MAX_MISSED,
+            Calendar::default(),
+        );
+        assert_eq!(
+            missed,
+            vec![first + Duration::hours(1), first + Duration::hours(2)]
+        );
+        assert!(pattern.has_remaining());
+        assert!(!pattern.count_occurrence() && !pattern.has_remaining());
+
+        let mut countdown = property_pattern("1h,30m,2h x");
+        let first = countdown.next(now_time(), Calendar::default()).unwrap();
+        let missed = countdown.take_missed(
+            first,
+            first + Duration::hours(1),
+            MAX_MISSED,
+            Calendar::default(),
+        );
+        assert_eq!(missed, vec![first + Duration::minutes(30)]);
+        assert_eq!(
+            countdown.next(now_time(), Calendar::default()),
+            Some(first + Duration::minutes(90))
+        );
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, mi, 0)
+            .unwrap()
+    }
+
+    const SHIFT_EARLIER: DstPolicy = DstPolicy {
+        gap: DstGap::ShiftForward,
+        overlap: DstOverlap::Earlier,
+    };
+    const SKIP_LATER: DstPolicy = DstPolicy {
+        gap: DstGap::Skip,
+        overlap: DstOverlap::Later,
+    };
+
+    #[test_case("every 1d 2:30 Europe/Amsterdam x", utc(2024, 3, 29, 12, 0), SHIFT_EARLIER, vec![utc(2024, 3, 30, 1, 30), utc(2024, 3, 31, 1, 30), utc(2024, 4, 1, 0, 30)] ; "europe spring forward shifted")]
+    #[test_case("every 1d 2:30 Europe/Amsterdam x", utc(2024, 3, 29, 12, 0), SKIP_LATER, vec![utc(2024, 3, 30, 1, 30), utc(2024, 4, 1, 0, 30), utc(2024, 4, 2, 0, 30)] ; "europe spring forward skipped")]
+    #[test_case("every 1d 2:30 Europe/Amsterdam x", utc(2024, 10, 25, 12, 0), SHIFT_EARLIER, vec![utc(2024, 10, 26, 0, 30), utc(2024, 10, 27, 0, 30), utc(2024, 10, 28, 1, 30)] ; "europe fall back earlier")]
+    #[test_case("every 1d 2:30 Europe/Amsterdam x", utc(2024, 10, 25, 12, 0), SKIP_LATER, vec![utc(2024, 10, 26, 0, 30), utc(2024, 10, 27, 1, 30), utc(2024, 10, 28, 1, 30)] ; "europe fall back later")]
+    #[test_case("every 1d 2:30 America/New_York x", utc(2024, 3, 8, 12, 0), SHIFT_EARLIER, vec![utc(2024, 3, 9, 7, 30), utc(2024, 3, 10, 7, 30), utc(2024, 3, 11, 6, 30)] ; "america spring forward shifted")]
+    #[test_case("every 1d 2:30 America/New_York x", utc(2024, 3, 8, 12, 0), SKIP_LATER, vec![utc(2024, 3, 9, 7, 30), utc(2024, 3, 11, 6, 30), utc(2024, 3, 12, 6, 30)] ; "america spring forward skipped")]
+    #[test_case("every 1d 1:30 America/New_York x", utc(2024, 11, 1, 12, 0), SHIFT_EARLIER, vec![utc(2024, 11, 2, 5, 30), utc(2024, 11, 3, 5, 30), utc(2024, 11, 4, 6, 30)] ; "america fall back earlier")]
+    #[test_case("every 1d 1:30 America/New_York x", utc(2024, 11, 1, 12, 0), SKIP_LATER, vec![utc(2024, 11, 2, 5, 30), utc(2024, 11, 3, 6, 30), utc(2024, 11, 4, 6, 30)] ; "america fall back later")]
+    #[serial]
+    fn test_dst_transitions(
+        s: &str,
+        now: NaiveDateTime,
+        dst: DstPolicy,
+        expected: Vec<NaiveDateTime>,
+    ) {
+        *TEST_TIMESTAMP.write().unwrap() = now.and_utc().timestamp();
+        let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        let calendar = Calendar {
+            dst,
+            ..Calendar::default()
+        };
+        assert_eq!(
+            pattern
+                .occurrences(now, calendar)
+                .take(3)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test_case(SHIFT_EARLIER, Some(utc(2024, 3, 31, 1, 30)) ; "shifted")]
+    #[test_case(SKIP_LATER, None ; "skipped")]
+    #[serial]
+    fn test_countdown_into_dst_gap(
+        dst: DstPolicy,
+        expected: Option<NaiveDateTime>,
+    ) {
+        let now = utc(2024, 3, 30, 1, 30);
+        *TEST_TIMESTAMP.write().unwrap() = now.and_utc().timestamp();
+        let parsed = parse_reminder("1d x").unwrap().pattern.unwrap();
+        let mut pattern =
+            Pattern::from_with_tz(parsed, chrono_tz::Europe::Amsterdam, None)
+                .unwrap();
+        let calendar = Calendar {
+            dst,
+            ..Calendar::default()
+        };
+        assert_eq!(pattern.next(now, calendar), expected);
+    }
+
+    #[test]
+    #[serial]
+    fn test_recurrence_offset() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            ("in 2 days at 10:00 call", vec![tz(2007, 2, 4, 10, 0, 0)]),
+            (
+                "in 1d at 9:00,18:00 walk",
+                vec![tz(2007, 2, 3, 9, 0, 0), tz(2007, 2, 3, 18, 0, 0)],
+            ),
+            (
+                "in 1 week on fri 9:00 report",
+                vec![
+                    tz(2007, 2, 9, 9, 0, 0),
+                    tz(2007, 2, 16, 9, 0, 0),
+                    tz(2007, 2, 23, 9, 0, 0),
+                    tz(2007, 3, 2, 9, 0, 0),
+                ],
+            ),
+            (
+                "after 2 weeks every mon 10:00 standup",
+                vec![
+                    tz(2007, 2, 19, 10, 0, 0),
+                    tz(2007, 2, 26, 10, 0, 0),
+                    tz(2007, 3, 5, 10, 0, 0),
+                    tz(2007, 3, 12, 10, 0, 0),
+                ],
+            ),
+            ("+1mo 15 10:00 pay", vec![tz(2007, 3, 15, 10, 0, 0)]),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(4).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_recurrence_timezone() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            ("10:00 UTC call", vec![tz(2007, 2, 2, 13, 0, 0)]),
+            ("at 9:00 Europe/Berlin call", vec![tz(2007, 2, 3, 11, 0, 0)]),
+            ("at 9:00 europe/berlin call", vec![tz(2007, 2, 3, 11, 0, 0)]),
+            ("10:00 +5 call", vec![tz(2007, 2, 3, 8, 0, 0)]),
+            ("10:00 UTC-3:00 call", vec![tz(2007, 2, 2, 16, 0, 0)]),
+            ("10:00 +05:30 call", vec![tz(2007, 2, 3, 7, 30, 0)]),
+            (
+                "on mon 15:00 America/New_York standup",
+                vec![tz(2007, 2, 5, 23, 0, 0), tz(2007, 2, 12, 23, 0, 0)],
+            ),
+            (
+                "every 1d 9:00 PST standup",
+                vec![tz(2007, 2, 2, 20, 0, 0), tz(2007, 2, 3, 20, 0, 0)],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(2).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+        // an offset without a zone of its own is kept as is
+        let parsed = parse_reminder("every 1d 10:00 UTC+5:30 call")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(pattern.timezone().to_string(), "UTC+05:30");
+        let serialized = serde_json::to_string(&pattern).unwrap();
+        let deserialized: Pattern = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.timezone(), pattern.timezone());
+        // abbreviations in lowercase are a part of the description
+        let parsed = parse_reminder("10:00 pst call").unwrap();
+        assert_eq!(parsed.description.unwrap().0, "pst call");
+    }
+
+    #[test]
+    #[serial]
+    fn test_spelled_intervals() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "every other day 9:00 walk",
+                vec![
+                    tz(2007, 2, 4, 9, 0, 0),
+                    tz(2007, 2, 6, 9, 0, 0),
+                    tz(2007, 2, 8, 9, 0, 0),
+                ],
+            ),
+            (
+                "every two weeks 10:00 sync",
+                vec![
+                    tz(2007, 2, 16, 10, 0, 0),
+                    tz(2007, 3, 2, 10, 0, 0),
+                    tz(2007, 3, 16, 10, 0, 0),
+                ],
+            ),
+            (
+                "13:00-14:00 every quarter hour stretch",
+                vec![
+                    tz(2007, 2, 2, 13, 0, 0),
+                    tz(2007, 2, 2, 13, 15, 0),
+                    tz(2007, 2, 2, 13, 30, 0),
+                ],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(3).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_iso_intervals() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "every P1W 9:00 weekly",
+                vec![tz(2007, 2, 9, 9, 0, 0), tz(2007, 2, 16, 9, 0, 0)],
+            ),
+            (
+                "13:00-14:00/PT20M stretch",
+                vec![tz(2007, 2, 2, 13, 0, 0), tz(2007, 2, 2, 13, 20, 0)],
+            ),
+            (
+                "13:00-14:00/0.5h stretch",
+                vec![tz(2007, 2, 2, 13, 0, 0), tz(2007, 2, 2, 13, 30, 0)],
+            ),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).take(2).collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_month_end_display_round_trip() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            ("last day of month 10:00 rent", "—/last day 10:00"),
+            ("2 days before end 10:00 invoices", "—/2d before end 10:00"),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            let displayed = pattern.to_string();
+            assert_eq!(displayed, expected);
+            let reparsed = parse_reminder(&displayed).unwrap().pattern.unwrap();
+            let reparsed =
+                Pattern::from_with_tz(reparsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(reparsed).take(3).collect::<Vec<_>>(),
+                get_all_times(pattern).take(3).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_exceptions() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            (
+                "-/mon-fri 9:00 except 05.02,7.02 standup",
+                vec![
+                    tz(2007, 2, 6, 9, 0, 0),
+                    tz(2007, 2, 8, 9, 0, 0),
+                    tz(2007, 2, 9, 9, 0, 0),
+                ],
+            ),
+            (
+                "- 9-18/1h except 13:00 stretch",
+                vec![
+                    tz(2007, 2, 2, 14, 0, 0),
+                    tz(2007, 2, 2, 15, 0, 0),
+                    tz(2007, 2, 2, 16, 0, 0),
+                ],
+            ),
+            (
+                "- except sat,sun 10:00 walk",
+                vec![
+                    tz(2007, 2, 5, 10, 0, 0),
+                    tz(2007, 2, 6, 10, 0, 0),
+                    tz(2007, 2, 7, 10, 0, 0),
+                ],
+            ),
+            (
+                "-/1mo 13:00 except 1.07-31.08 water the garden",
+                vec![
+                    tz(2007, 2, 2, 13, 0, 0),
+                    tz(2007, 3, 2, 13, 0, 0),
+                    tz(2007, 4, 2, 13, 0, 0),
+                    tz(2007, 5, 2, 13, 0, 0),
+                    tz(2007, 6, 2, 13, 0, 0),
+                    tz(2007, 9, 2, 13, 0, 0),
+                ],
+            ),
+            (
+                "-/1mo 13:00 except dec 1-28 feb, 1.05.2007 mow the lawn",
+                vec![
+                    tz(2007, 3, 2, 13, 0, 0),
+                    tz(2007, 4, 2, 13, 0, 0),
+                    tz(2007, 5, 2, 13, 0, 0),
+                    tz(2007, 6, 2, 13, 0, 0),
+                    tz(2007, 7, 2, 13, 0, 0),
+                    tz(2007, 8, 2, 13, 0, 0),
+                ],
+            ),
+            ("- 10:00 except mon-sun never", vec![]),
+        ] {
+            let parsed = parse_reminder(s).unwrap().pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern)
+                    .take(expected.len().max(1))
+                    .collect::<Vec<_>>(),
+                expected,
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_exceptions_display_round_trip() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed =
+            parse_reminder("- 9-18/1h except sat,sun,25.12,13:00 work")
+                .unwrap()
+                .pattern
+                .unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        let displayed = pattern.to_string();
+        assert_eq!(displayed, "— 09:00—18:00/1h except Sat,Sun,25.12,13:00");
+        let reparsed = parse_reminder(&displayed).unwrap().pattern.unwrap();
+        let reparsed = Pattern::from_with_tz(reparsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(reparsed).take(20).collect::<Vec<_>>(),
+            get_all_times(pattern).take(20).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_exceptions_serde() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("- 10:00 except 1.05 holiday")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        let json = serde_json::to_string(&pattern).unwrap();
+        let deserialized: Pattern = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.to_string(), pattern.to_string());
+
+        let parsed = parse_reminder("- 10:00 no holidays")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert!(!serde_json::to_string(&pattern).unwrap().contains("exc"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_12_hour_time_range() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let s = "- 9am-5pm every 3h 12-hour range";
+        let parsed_rem = parse_reminder(s).unwrap();
+        assert_eq!(
+            parsed_rem.description.map(|x| x.0),
+            Some("12-hour range".to_owned())
+        );
+        let parsed = parsed_rem.pattern.unwrap();
+        let pattern = Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+        assert_eq!(
+            get_all_times(pattern).take(5).collect::<Vec<_>>(),
+            vec![
+                tz(2007, 2, 2, 15, 0, 0),
+                tz(2007, 2, 3, 9, 0, 0),
+                tz(2007, 2, 3, 12, 0, 0),
+                tz(2007, 2, 3, 15, 0, 0),
+                tz(2007, 2, 4, 9, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_relative_dates() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        for (s, expected) in [
+            ("today 18:00 drinks", tz(2007, 2, 2, 18, 0, 0)),
+            ("tomorrow 9:00 dentist", tz(2007, 2, 3, 9, 0, 0)),
+            ("day after tomorrow at 10 call", tz(2007, 2, 4, 10, 0, 0)),
+            ("this fri 18:00 drinks", tz(2007, 2, 2, 18, 0, 0)),
+            ("next fri 18:00 drinks", tz(2007, 2, 9, 18, 0, 0)),
+            ("next Monday 8:30 standup", tz(2007, 2, 5, 8, 30, 0)),
+        ] {
+            let parsed_rem = parse_reminder(s).unwrap();
+            assert!(parsed_rem.description.is_some());
+            let parsed = parsed_rem.pattern.unwrap();
+            let pattern =
+                Pattern::from_with_tz(parsed, *TEST_TZ, None).unwrap();
+            assert_eq!(
+                get_all_times(pattern).collect::<Vec<_>>(),
+                vec![expected]
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_relative_date_in_the_past() {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let parsed = parse_reminder("today 9:00 missed").unwrap().pattern;
+        assert!(
+            Pattern::from_with_tz(parsed.unwrap(), *TEST_TZ, None).is_none()
+        );
+    }
+
+    #[test]
+    fn test_intervals_display() {
+        let int1 = Interval {
+            years: 1,
+            months: 2,
+            weeks: 3,
+            days: 4,
+            hours: 5,
+            minutes: 6,
+            seconds: 7,
+        };
+        assert_eq!(int1.to_string(), "1y2mo3w4d5h6m7s");
+        let int2 = Interval {
+            years: 0,
+            months: 0,
+            weeks: 0,
+            days: 0,
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+        };
+        assert_eq!(int2.to_string(), "");
+
+        let date_int1 = DateInterval {
+            years: 1,
+            months: 2,
+            weeks: 3,
+            days: 4,
+        };
+        assert_eq!(date_int1.to_string(), "1y2mo3w4d");
+        let date_int2 = DateInterval {
+            years: 0,
+            months: 0,
+            weeks: 0,
+            days: 0,
+        };
+        assert_eq!(date_int2.to_string(), "");
+
+        let time_int1 = TimeInterval {
+            hours: 1,
+            minutes: 2,
+            seconds: 3,
+        };
+        assert_eq!(time_int1.to_string(), "1h2m3s");
+        let time_int2 = TimeInterval {
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+        };
+        assert_eq!(time_int2.to_string(), "");
+    }
+}
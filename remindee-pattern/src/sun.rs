@@ -0,0 +1,114 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// Altitude of the sun's center at sunrise and sunset in degrees,
+/// accounting for the refraction and the radius of the solar disc
+const SUN_ALTITUDE: f64 = -0.833;
+/// Julian day of 2000-01-01 12:00 UTC
+const J2000: f64 = 2451545.0;
+/// Julian day of the Unix epoch
+const UNIX_EPOCH: f64 = 2440587.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum SunEvent {
+    #[serde(rename = "rise")]
+    Sunrise,
+    #[serde(rename = "set")]
+    Sunset,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    #[serde(rename = "lat")]
+    pub latitude: f64,
+    #[serde(rename = "lng")]
+    pub longitude: f64,
+}
+
+impl Location {
+    /// UTC time of the sun event on the date, rounded to a minute,
+    /// or `None` if the sun doesn't rise or set that day
+    /// (sunrise equation with the NOAA approximations)
+    pub(crate) fn sun_event(
+        &self,
+        event: SunEvent,
+        date: NaiveDate,
+    ) -> Option<NaiveDateTime> {
+        let days = (date - NaiveDate::from_ymd_opt(2000, 1, 1)?).num_days();
+        let mean_noon = days as f64 - self.longitude / 360.0;
+        let anomaly = (357.5291 + 0.98560028 * mean_noon)
+            .rem_euclid(360.0)
+            .to_radians();
+        let center = 1.9148 * anomaly.sin()
+            + 0.02 * (2.0 * anomaly).sin()
+            + 0.0003 * (3.0 * anomaly).sin();
+        let ecliptic_longitude = (anomaly.to_degrees() + center + 282.9372)
+            .rem_euclid(360.0)
+            .to_radians();
+        let transit = J2000 + mean_noon + 0.0053 * anomaly.sin()
+            - 0.0069 * (2.0 * ecliptic_longitude).sin();
+        let declination =
+            (ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin()).asin();
+        let latitude = self.latitude.to_radians();
+        let cos_hour_angle = (SUN_ALTITUDE.to_radians().sin()
+            - latitude.sin() * declination.sin())
+            / (latitude.cos() * declination.cos());
+        // a polar day or night
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None;
+        }
+        let hour_angle = cos_hour_angle.acos().to_degrees() / 360.0;
+        let julian_day = match event {
+            SunEvent::Sunrise => transit - hour_angle,
+            SunEvent::Sunset => transit + hour_angle,
+        };
+        let minutes = ((julian_day - UNIX_EPOCH) * 1440.0).round() as i64;
+        DateTime::from_timestamp(minutes * 60, 0).map(|time| time.naive_utc())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    const AMSTERDAM: Location = Location {
+        latitude: 52.37,
+        longitude: 4.9,
+    };
+    const SYDNEY: Location = Location {
+        latitude: -33.87,
+        longitude: 151.21,
+    };
+    const TROMSO: Location = Location {
+        latitude: 69.65,
+        longitude: 18.96,
+    };
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, mi, 0)
+            .unwrap()
+    }
+
+    #[test_case(AMSTERDAM, SunEvent::Sunrise, NaiveDate::from_ymd_opt(2025, 6, 21).unwrap(), utc(2025, 6, 21, 3, 18) ; "amsterdam summer sunrise")]
+    #[test_case(AMSTERDAM, SunEvent::Sunset, NaiveDate::from_ymd_opt(2025, 6, 21).unwrap(), utc(2025, 6, 21, 20, 6) ; "amsterdam summer sunset")]
+    #[test_case(SYDNEY, SunEvent::Sunset, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), utc(2025, 1, 1, 9, 10) ; "sydney sunset")]
+    fn test_sun_event(
+        location: Location,
+        event: SunEvent,
+        date: NaiveDate,
+        expected: NaiveDateTime,
+    ) {
+        let time = location.sun_event(event, date).unwrap();
+        // the approximation is good to a couple of minutes
+        assert!((time - expected).num_minutes().abs() <= 2, "{time}");
+    }
+
+    #[test_case(SunEvent::Sunset, NaiveDate::from_ymd_opt(2025, 6, 21).unwrap() ; "polar day")]
+    #[test_case(SunEvent::Sunrise, NaiveDate::from_ymd_opt(2025, 12, 21).unwrap() ; "polar night")]
+    fn test_no_sun_event(event: SunEvent, date: NaiveDate) {
+        assert_eq!(TROMSO.sun_event(event, date), None);
+    }
+}
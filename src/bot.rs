@@ -1,3 +1,4 @@
+use crate::catch_up::{get_user_catch_up, CatchUp, MAX_MISSED_OCCURRENCES};
 use crate::cli::CLI;
 #[cfg(not(test))]
 use crate::db::Database;
@@ -9,13 +10,13 @@ use crate::format;
 use crate::handlers::{get_handler, Command, State};
 use crate::lang::get_user_language;
 use crate::parsers::now_time;
-use crate::serializers::Pattern;
 use crate::tg::send_message;
-use crate::tz::get_user_timezone;
+use crate::tz::{get_user_calendar, get_user_timezone};
 use chrono::prelude::*;
 use chrono::{NaiveDateTime, TimeDelta, Utc};
 use chrono_tz::Tz;
 use cron_parser::parse as parse_cron;
+use remindee_pattern::Pattern;
 use sea_orm::{ActiveValue::NotSet, IntoActiveModel};
 use serde_json::{from_str, to_string};
 use std::cmp::max;
@@ -25,8 +26,17 @@ use teloxide::dispatching::dialogue::{ErasedStorage, SqliteStorage, Storage};
 use teloxide::{prelude::*, utils::command::BotCommands};
 use tokio::time::Instant;
 
+/// Append the catch-up note of a late delivery to the message
+fn with_note(text: String, note: Option<String>) -> String {
+    match note {
+        Some(note) => format!("{text}\n\n{note}"),
+        None => text,
+    }
+}
+
 async fn send_reminder(
     reminder: &reminder::Model,
+    note: Option<String>,
     user_timezone: Tz,
     bot: &Bot,
 ) -> Result<(), Error> {
@@ -34,7 +44,7 @@ async fn send_reminder(
         &reminder.clone().into_active_model(),
         user_timezone,
     );
-    send_message(&text, bot, ChatId(reminder.chat_id))
+    send_message(&with_note(text, note), bot, ChatId(reminder.chat_id))
         .await
         .map(|_| ())
         .map_err(From::from)
@@ -43,6 +53,7 @@ async fn send_reminder(
 async fn send_cron_reminder(
     reminder: &cron_reminder::Model,
     next_reminder: Option<&cron_reminder::Model>,
+    note: Option<String>,
     user_lang: String,
     user_timezone: Tz,
     bot: &Bot,
@@ -53,13 +64,73 @@ async fn send_cron_reminder(
         user_lang,
         user_timezone,
     );
-    send_message(&text, bot, ChatId(reminder.chat_id))
+    send_message(&with_note(text, note), bot, ChatId(reminder.chat_id))
         .await
         .map(|_| ())
         .map_err(From::from)
 }
 
-async fn process_due_reminders(db: &Database, bot: &Bot) {
+/// Times of the cron reminder's occurrences after its time up to `now`
+fn missed_cron_times(
+    cron_reminder: &cron_reminder::Model,
+    user_timezone: Tz,
+    now: NaiveDateTime,
+) -> Vec<NaiveDateTime> {
+    let mut times = vec![];
+    let mut cur = cron_reminder.time;
+    while times.len() < MAX_MISSED_OCCURRENCES {
+        match parse_cron(
+            &cron_reminder.cron_expr,
+            &user_timezone.from_utc_datetime(&cur),
+        ) {
+            Ok(time) if time.naive_utc() <= now => {
+                cur = time.naive_utc();
+                times.push(cur);
+            }
+            _ => break,
+        }
+    }
+    times
+}
+
+/// Occurrences of the reminder's pattern after its time up to `until`,
+/// along with the reminder of the occurrence that follows them
+async fn advance_reminder(
+    db: &Database,
+    user_id: UserId,
+    reminder: &reminder::Model,
+    until: NaiveDateTime,
+) -> (Vec<NaiveDateTime>, Option<reminder::Model>) {
+    let Some(ref serialized) = reminder.pattern else {
+        return (vec![], None);
+    };
+    let mut pattern: Pattern = from_str(serialized).unwrap();
+    if !pattern.count_occurrence() {
+        return (vec![], None);
+    }
+    let calendar = get_user_calendar(db, user_id).await;
+    let missed = pattern.take_missed(
+        reminder.time,
+        until,
+        MAX_MISSED_OCCURRENCES,
+        calendar,
+    );
+    let next_reminder = pattern
+        .next(max(reminder.time, until), calendar)
+        .filter(|_| pattern.has_remaining())
+        .map(|next_time| reminder::Model {
+            time: next_time,
+            pattern: to_string(&pattern).ok(),
+            ..reminder.clone()
+        });
+    (missed, next_reminder)
+}
+
+async fn process_due_reminders(
+    db: &Database,
+    bot: &Bot,
+    default_catch_up: CatchUp,
+) {
     let reminders = db
         .get_active_reminders()
         .await
@@ -69,35 +140,55 @@ async fn process_due_reminders(db: &Database, bot: &Bot) {
             if let Ok(Some(user_timezone)) =
                 get_user_timezone(db, user_id).await
             {
-                let mut next_reminder = None;
-                if let Some(ref serialized) = reminder.pattern {
-                    let mut pattern: Pattern = from_str(serialized).unwrap();
-                    let lower_bound = max(reminder.time, now_time());
-                    if let Some(next_time) = pattern.next(lower_bound) {
-                        next_reminder = Some(reminder::Model {
-                            time: next_time,
-                            pattern: to_string(&pattern).ok(),
-                            ..reminder.clone()
-                        });
+                let now = now_time();
+                let (missed, mut next_reminder) =
+                    advance_reminder(db, user_id, &reminder, now).await;
+                let mut due = vec![reminder.time];
+                due.extend(missed);
+                let catch_up =
+                    get_user_catch_up(db, user_id, default_catch_up).await;
+                let lang = get_user_language(db, user_id).await;
+                let mut delivered = None;
+                let mut sent = true;
+                for delivery in catch_up.deliveries(&due, now) {
+                    let reminder = reminder::Model {
+                        time: delivery.time(),
+                        ..reminder.clone()
+                    };
+                    let note = delivery.note(user_timezone, lang.code());
+                    if let Err(err) =
+                        send_reminder(&reminder, note, user_timezone, bot).await
+                    {
+                        log::error!("{}", err);
+                        sent = false;
+                        break;
                     }
+                    delivered = Some(delivery.time());
                 }
-                if send_reminder(&reminder, user_timezone, bot).await.is_ok() {
-                    db.delete_reminder(reminder.id).await.unwrap_or_else(
-                        |err| {
-                            log::error!("{}", err);
-                        },
-                    );
-                    if let Some(next_reminder) = next_reminder {
-                        let mut next_reminder: reminder::ActiveModel =
-                            next_reminder.into();
-                        next_reminder.id = NotSet;
-                        db.insert_reminder(next_reminder)
+                if !sent {
+                    // keep only the occurrences that weren't sent
+                    // so that the next tick doesn't repeat the others
+                    let Some(delivered) = delivered else {
+                        continue;
+                    };
+                    next_reminder =
+                        advance_reminder(db, user_id, &reminder, delivered)
                             .await
-                            .map(|_| ())
-                            .unwrap_or_else(|err| {
-                                log::error!("{}", err);
-                            });
-                    }
+                            .1;
+                }
+                db.delete_reminder(reminder.id).await.unwrap_or_else(|err| {
+                    log::error!("{}", err);
+                });
+                if let Some(next_reminder) = next_reminder {
+                    let mut next_reminder: reminder::ActiveModel =
+                        next_reminder.into();
+                    next_reminder.id = NotSet;
+                    db.insert_reminder(next_reminder)
+                        .await
+                        .map(|_| ())
+                        .unwrap_or_else(|err| {
+                            log::error!("{}", err);
+                        });
                 }
             }
         }
@@ -111,9 +202,10 @@ async fn process_due_reminders(db: &Database, bot: &Bot) {
             if let Ok(Some(user_timezone)) =
                 get_user_timezone(db, user_id).await
             {
+                let now = now_time();
                 let new_time = parse_cron(
                     &cron_reminder.cron_expr,
-                    &user_timezone.from_utc_datetime(&now_time()),
+                    &user_timezone.from_utc_datetime(&now),
                 )
                 .map(|user_time| user_time.with_timezone(&Utc));
                 let new_cron_reminder = match new_time {
@@ -126,35 +218,67 @@ async fn process_due_reminders(db: &Database, bot: &Bot) {
                         None
                     }
                 };
-                match send_cron_reminder(
+                let mut due = vec![cron_reminder.time];
+                due.extend(missed_cron_times(
                     &cron_reminder,
-                    new_cron_reminder.as_ref(),
-                    get_user_language(db, user_id).await.code().to_owned(),
                     user_timezone,
-                    bot,
-                )
-                .await
-                {
-                    Ok(()) => {
-                        db.delete_cron_reminder(cron_reminder.id)
-                            .await
-                            .unwrap_or_else(|err| {
-                                log::error!("{}", err);
-                            });
-                        if let Some(new_cron_reminder) = new_cron_reminder {
-                            let mut new_cron_reminder: cron_reminder::ActiveModel = new_cron_reminder.into();
-                            new_cron_reminder.id = NotSet;
-                            db.insert_cron_reminder(new_cron_reminder)
-                                .await
-                                .map(|_| ())
-                                .unwrap_or_else(|err| {
-                                    log::error!("{}", err);
-                                });
-                        }
-                    }
-                    Err(err) => {
+                    now,
+                ));
+                let catch_up =
+                    get_user_catch_up(db, user_id, default_catch_up).await;
+                let lang = get_user_language(db, user_id).await;
+                let mut delivered = None;
+                let mut sent = true;
+                for delivery in catch_up.deliveries(&due, now) {
+                    let cron_reminder = cron_reminder::Model {
+                        time: delivery.time(),
+                        ..cron_reminder.clone()
+                    };
+                    if let Err(err) = send_cron_reminder(
+                        &cron_reminder,
+                        new_cron_reminder.as_ref(),
+                        delivery.note(user_timezone, lang.code()),
+                        lang.code().to_owned(),
+                        user_timezone,
+                        bot,
+                    )
+                    .await
+                    {
                         log::error!("{}", err);
+                        sent = false;
+                        break;
                     }
+                    delivered = Some(delivery.time());
+                }
+                let new_cron_reminder = if sent {
+                    new_cron_reminder
+                } else {
+                    // resume from the first occurrence that wasn't sent
+                    let Some(delivered) = delivered else {
+                        continue;
+                    };
+                    due.into_iter().find(|&time| time > delivered).map(|time| {
+                        cron_reminder::Model {
+                            time,
+                            ..cron_reminder.clone()
+                        }
+                    })
+                };
+                db.delete_cron_reminder(cron_reminder.id)
+                    .await
+                    .unwrap_or_else(|err| {
+                        log::error!("{}", err);
+                    });
+                if let Some(new_cron_reminder) = new_cron_reminder {
+                    let mut new_cron_reminder: cron_reminder::ActiveModel =
+                        new_cron_reminder.into();
+                    new_cron_reminder.id = NotSet;
+                    db.insert_cron_reminder(new_cron_reminder)
+                        .await
+                        .map(|_| ())
+                        .unwrap_or_else(|err| {
+                            log::error!("{}", err);
+                        });
                 }
             }
         }
@@ -170,7 +294,7 @@ async fn deadline_from_datetime(dt: NaiveDateTime) -> Instant {
 
 /// Wait for the next reminder to send or some change in the database.
 /// Send and update/delete reminders.
-async fn poll_reminders(db: Arc<Database>, bot: Bot) {
+async fn poll_reminders(db: Arc<Database>, bot: Bot, catch_up: CatchUp) {
     const DEFAULT_CHECK_INTERVAL: TimeDelta = TimeDelta::seconds(60);
 
     let next_deadline = tokio::time::sleep_until(Instant::now());
@@ -192,7 +316,7 @@ async fn poll_reminders(db: Arc<Database>, bot: Bot) {
                 next_deadline.as_mut().reset(get_next_reminder_time().await);
             }
             () = &mut next_deadline => {
-                process_due_reminders(&db, &bot).await;
+                process_due_reminders(&db, &bot, catch_up).await;
 
                 next_deadline.as_mut().reset(get_next_reminder_time().await);
             }
@@ -235,7 +359,12 @@ pub(crate) async fn run() {
 
     let db_clone = db.clone();
 
-    tokio::spawn(poll_reminders(db_clone, bot.clone()));
+    let catch_up = CatchUp {
+        policy: CLI.catch_up,
+        threshold: TimeDelta::minutes(CLI.catch_up_threshold.into()),
+    };
+
+    tokio::spawn(poll_reminders(db_clone, bot.clone(), catch_up));
 
     let storage = init_dialogue_storage().await;
 
@@ -265,6 +394,8 @@ mod test {
     use chrono_tz::Tz;
     use dptree::deps;
     use mockall::predicate::eq;
+    use remindee_parser::{ParseError, ParseErrorKind};
+    use remindee_pattern::{Calendar, Pattern};
     use sea_orm::IntoActiveModel;
     use serial_test::serial;
     use teloxide::{
@@ -278,7 +409,8 @@ mod test {
     };
     use teloxide_tests::mock_bot::DistributionKey;
     use teloxide_tests::{
-        IntoUpdate, MockBot, MockCallbackQuery, MockMessageText,
+        IntoUpdate, MockBot, MockCallbackQuery, MockMessageLocation,
+        MockMessageText,
     };
 
     use super::State;
@@ -407,6 +539,52 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_set_location() {
+        let message = MockMessageLocation::new().latitude(52.37).longitude(4.9);
+        let mut db = MockDatabase::new();
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_insert_or_update_user_location()
+            .withf(|_, latitude, longitude| {
+                *latitude == 52.37 && *longitude == 4.9
+            })
+            .returning(|_, _, _| Ok(()));
+        db.expect_insert_or_update_user_timezone()
+            .withf(|_, tz_name| tz_name == "Europe/Amsterdam")
+            .returning(|_, _| Ok(()));
+        let mut bot = mock_bot(db, message);
+        bot.dispatch_and_check_last_text(
+            &TgResponse::ChosenTimezone("Europe/Amsterdam".to_owned())
+                .to_string(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_sun_reminder_without_location() {
+        *TEST_TIMESTAMP.write().unwrap() = mock_timezone()
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            .timestamp();
+        let message = MockMessageText::new().text("every 1d sunset lights");
+        let mut db = MockDatabase::new();
+        db.expect_get_user_timezone_name()
+            .returning(|_| Ok(Some(mock_timezone_name())));
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_get_user_holidays_country()
+            .returning(|_| Ok(None));
+        db.expect_get_user_location().returning(|_| Ok(None));
+        db.expect_get_user_dst_policy().returning(|_| Ok(None));
+        let mut bot = mock_bot(db, message);
+        bot.dispatch_and_check_last_text(
+            &TgResponse::LocationRequired.to_string(),
+        )
+        .await;
+    }
+
     macro_rules! resp {
         ($bot:expr, $field:ident, $($subfields:tt)+) => {
             $bot.get_responses().$field.iter().map(|m| (m.$($subfields)+).clone()).collect::<Vec<_>>()
@@ -875,6 +1053,47 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_list_reminder_in_other_timezone() {
+        *TEST_TIMESTAMP.write().unwrap() = mock_timezone()
+            .with_ymd_and_hms(2024, 1, 2, 12, 0, 0)
+            .unwrap()
+            .timestamp();
+        let mut db = MockDatabase::new();
+        let tz = mock_timezone();
+        db.expect_get_user_timezone_name()
+            .returning(|_| Ok(Some(mock_timezone_name())));
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        let parsed = remindee_parser::parse_reminder("10:00 America/New_York")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern = Pattern::from_with_tz(parsed, tz, None).unwrap();
+        let rem = reminder::Model {
+            time: NaiveDate::from_ymd_opt(2024, 1, 2)
+                .unwrap()
+                .and_hms_opt(15, 0, 0)
+                .unwrap(),
+            desc: "call".to_owned(),
+            pattern: Some(serde_json::to_string(&pattern).unwrap()),
+            ..basic_mock_reminder()
+        };
+        let rem_clone = rem.clone();
+        db.expect_get_sorted_reminders().returning(move |_| {
+            Ok(vec![Box::new(rem_clone.clone().into_active_model())])
+        });
+        let listed = rem.into_active_model().to_unescaped_string(tz);
+        assert_eq!(listed, "16:00 <call> [10:00 America/New_York]");
+        let message = MockMessageText::new().text("/list");
+        let mut bot = mock_bot(db, message);
+        bot.dispatch_and_check_last_text(
+            &TgResponse::RemindersList(listed).to_string(),
+        )
+        .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_edit_reminder_not_found() {
@@ -1062,6 +1281,10 @@ mod test {
             .returning(|_| Ok(Some(mock_timezone_name())));
         db.expect_get_user_language_name()
             .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_get_user_holidays_country()
+            .returning(|_| Ok(None));
+        db.expect_get_user_location().returning(|_| Ok(None));
+        db.expect_get_user_dst_policy().returning(|_| Ok(None));
         db.expect_insert_reminder()
             .returning(move |_| Ok(rem_clone.clone().into()));
         db.expect_set_reminder_reply_id().returning(|_, _| Ok(()));
@@ -1073,6 +1296,279 @@ mod test {
             .to_string(),
         )
         .await;
+        assert_eq!(
+            bot.get_responses().sent_messages[0]
+                .reply_markup()
+                .unwrap()
+                .inline_keyboard,
+            vec![vec![InlineKeyboardButton {
+                text: "Show next 10".to_string(),
+                kind: CallbackData("preview::upcoming".to_string()),
+            }]]
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_preview() {
+        *TEST_TIMESTAMP.write().unwrap() = mock_timezone()
+            .with_ymd_and_hms(2024, 3, 28, 0, 0, 0)
+            .unwrap()
+            .timestamp();
+        let message =
+            MockMessageText::new().text("/preview every 1d 10:00 test");
+        let mut db = MockDatabase::new();
+        db.expect_get_user_timezone_name()
+            .returning(|_| Ok(Some(mock_timezone_name())));
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_get_user_holidays_country()
+            .returning(|_| Ok(None));
+        db.expect_get_user_location().returning(|_| Ok(None));
+        db.expect_get_user_dst_policy().returning(|_| Ok(None));
+        let mut bot = mock_bot(db, message);
+        // the times stay at 10:00 across the switch to the summer time
+        bot.dispatch_and_check_last_text(
+            &TgResponse::UpcomingTimes(
+                [
+                    "28.03.2024 10:00 CET",
+                    "29.03.2024 10:00 CET",
+                    "30.03.2024 10:00 CET",
+                    "31.03.2024 10:00 CEST",
+                    "01.04.2024 10:00 CEST",
+                    "02.04.2024 10:00 CEST",
+                    "03.04.2024 10:00 CEST",
+                    "04.04.2024 10:00 CEST",
+                    "05.04.2024 10:00 CEST",
+                    "06.04.2024 10:00 CEST",
+                ]
+                .join("\n"),
+            )
+            .to_string(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_show_upcoming_times() {
+        let mut db = MockDatabase::new();
+        db.expect_get_user_timezone_name()
+            .returning(|_| Ok(Some(mock_timezone_name())));
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_get_user_holidays_country()
+            .returning(|_| Ok(None));
+        db.expect_get_user_location().returning(|_| Ok(None));
+        db.expect_get_user_dst_policy().returning(|_| Ok(None));
+        db.expect_get_reminder_by_reply_id().returning(|_| Ok(None));
+        db.expect_get_cron_reminder_by_reply_id()
+            .returning(|_| Ok(Some(basic_mock_cron_reminder())));
+        let mut bot = mock_bot(
+            db,
+            MockCallbackQuery::new()
+                .data("preview::upcoming")
+                .message(MockMessageText::new().build()),
+        );
+        bot.dispatch_and_check_last_text(
+            &TgResponse::UpcomingTimes(
+                (2..12)
+                    .map(|minute| format!("02.02.2024 02:{minute:02} CET"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+            .to_string(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_advance_partly_sent_reminder() {
+        *TEST_TIMESTAMP.write().unwrap() = mock_timezone()
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            .timestamp();
+        let mut db = MockDatabase::new();
+        db.expect_get_user_holidays_country()
+            .returning(|_| Ok(None));
+        db.expect_get_user_location().returning(|_| Ok(None));
+        db.expect_get_user_dst_policy().returning(|_| Ok(None));
+        let parsed = remindee_parser::parse_reminder("every 1d 10:00 x5")
+            .unwrap()
+            .pattern
+            .unwrap();
+        let pattern =
+            Pattern::from_with_tz(parsed, mock_timezone(), None).unwrap();
+        let time = |day| {
+            NaiveDate::from_ymd_opt(2024, 1, day)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap()
+        };
+        let rem = reminder::Model {
+            time: time(1),
+            pattern: Some(serde_json::to_string(&pattern).unwrap()),
+            ..basic_mock_reminder()
+        };
+        // the occurrences of the 1st and the 2nd were sent before a failure
+        let (missed, next_reminder) =
+            super::advance_reminder(&db, UserId(1), &rem, time(2)).await;
+        assert_eq!(missed, vec![time(2)]);
+        let next_reminder = next_reminder.unwrap().into_active_model();
+        assert_eq!(
+            next_reminder.upcoming_times(
+                mock_timezone(),
+                Calendar::default(),
+                10
+            ),
+            vec![time(3), time(4), time(5)]
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_invalid_reminder_hint() {
+        *TEST_TIMESTAMP.write().unwrap() = mock_timezone()
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            .timestamp();
+        let message = MockMessageText::new().text("31.02 10:00 test");
+        let mut db = MockDatabase::new();
+        db.expect_get_user_timezone_name()
+            .returning(|_| Ok(Some(mock_timezone_name())));
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_get_user_holidays_country()
+            .returning(|_| Ok(None));
+        db.expect_get_user_location().returning(|_| Ok(None));
+        db.expect_get_user_dst_policy().returning(|_| Ok(None));
+        let response = TgResponse::InvalidReminder(
+            "31.02 10:00 test".to_owned(),
+            ParseError {
+                span: 0..5,
+                kind: ParseErrorKind::InvalidDate,
+            },
+        );
+        assert_eq!(
+            response.to_unescaped_string_lang("en"),
+            "⚠️ Couldn't understand the reminder at «31.02»: there's no such date."
+        );
+        let mut bot = mock_bot(db, message);
+        bot.dispatch_and_check_last_text(&response.to_string())
+            .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_did_you_mean() {
+        *TEST_TIMESTAMP.write().unwrap() = mock_timezone()
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            .timestamp();
+        let message = MockMessageText::new().text("every 2 hourz drink");
+        let mut db = MockDatabase::new();
+        let rem = basic_mock_reminder();
+        let rem_clone = rem.clone();
+        db.expect_get_user_timezone_name()
+            .returning(|_| Ok(Some(mock_timezone_name())));
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_get_user_holidays_country()
+            .returning(|_| Ok(None));
+        db.expect_get_user_location().returning(|_| Ok(None));
+        db.expect_get_user_dst_policy().returning(|_| Ok(None));
+        db.expect_get_reminder_by_msg_id().returning(|_| Ok(None));
+        db.expect_get_cron_reminder_by_msg_id()
+            .returning(|_| Ok(None));
+        db.expect_insert_reminder()
+            .withf(|rem| rem.desc.as_ref() == "drink")
+            .returning(move |_| Ok(rem_clone.clone().into()));
+        db.expect_set_reminder_reply_id().returning(|_, _| Ok(()));
+        let mut bot = mock_bot(db, message);
+        bot.dispatch().await;
+        let reply = bot.get_responses().sent_messages[0].clone();
+        assert_eq!(
+            reply.reply_markup().unwrap().inline_keyboard,
+            vec![vec![InlineKeyboardButton {
+                text: "Did you mean: every 2 hours drink?".to_string(),
+                kind: CallbackData("didyoumean::apply".to_string()),
+            }]]
+        );
+
+        bot.update(
+            MockCallbackQuery::new()
+                .data("didyoumean::apply")
+                .message(reply),
+        );
+        bot.dispatch_and_check_last_text(
+            &TgResponse::SuccessInsert(
+                rem.into_active_model().to_unescaped_string(mock_timezone()),
+            )
+            .to_string(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_did_you_mean_in_group() {
+        *TEST_TIMESTAMP.write().unwrap() = mock_timezone()
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            .timestamp();
+        let mut message =
+            MockMessageText::new().text("/set call mom tomorow 10:00");
+        message.chat.id.0 = -1;
+        let mut db = MockDatabase::new();
+        let rem = basic_mock_reminder();
+        let rem_clone = rem.clone();
+        db.expect_get_user_timezone_name()
+            .returning(|_| Ok(Some(mock_timezone_name())));
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_get_user_holidays_country()
+            .returning(|_| Ok(None));
+        db.expect_get_user_location().returning(|_| Ok(None));
+        db.expect_get_user_dst_policy().returning(|_| Ok(None));
+        db.expect_get_reminder_by_msg_id().returning(|_| Ok(None));
+        db.expect_get_cron_reminder_by_msg_id()
+            .returning(|_| Ok(None));
+        db.expect_insert_reminder()
+            .withf(|rem| {
+                ["call mom tomorow", "call mom"]
+                    .contains(&rem.desc.as_ref().as_str())
+            })
+            .returning(move |_| Ok(rem_clone.clone().into()));
+        db.expect_set_reminder_reply_id().returning(|_, _| Ok(()));
+        let mut bot = mock_bot(db, message);
+        bot.dispatch().await;
+        let reply = bot.get_responses().sent_messages[0].clone();
+        assert_eq!(
+            reply.reply_markup().unwrap().inline_keyboard,
+            vec![
+                vec![InlineKeyboardButton {
+                    text: "Show next 10".to_string(),
+                    kind: CallbackData("preview::upcoming".to_string()),
+                }],
+                vec![InlineKeyboardButton {
+                    text: "Did you mean: call mom tomorrow 10:00?".to_string(),
+                    kind: CallbackData("didyoumean::apply".to_string()),
+                }]
+            ]
+        );
+
+        bot.update(
+            MockCallbackQuery::new()
+                .data("didyoumean::apply")
+                .message(reply),
+        );
+        bot.dispatch_and_check_last_text(
+            &TgResponse::SuccessInsert(
+                rem.into_active_model().to_unescaped_string(mock_timezone()),
+            )
+            .to_string(),
+        )
+        .await;
     }
 
     #[tokio::test]
@@ -1088,10 +1584,32 @@ mod test {
             vec![MockMarkup {
                 media_text: TgResponse::SettingsMenu.to_string(),
                 markup: InlineKeyboardMarkup {
-                    inline_keyboard: vec![vec![InlineKeyboardButton {
-                        text: "Change language".to_string(),
-                        kind: CallbackData("settings::change_lang".to_string()),
-                    }]],
+                    inline_keyboard: vec![
+                        vec![InlineKeyboardButton {
+                            text: "Change language".to_string(),
+                            kind: CallbackData(
+                                "settings::change_lang".to_string(),
+                            ),
+                        }],
+                        vec![InlineKeyboardButton {
+                            text: "Change holiday calendar".to_string(),
+                            kind: CallbackData(
+                                "settings::change_holidays".to_string(),
+                            ),
+                        }],
+                        vec![InlineKeyboardButton {
+                            text: "Missed reminders".to_string(),
+                            kind: CallbackData(
+                                "settings::change_catch_up".to_string(),
+                            ),
+                        }],
+                        vec![InlineKeyboardButton {
+                            text: "Clock changes".to_string(),
+                            kind: CallbackData(
+                                "settings::change_dst".to_string(),
+                            ),
+                        }],
+                    ],
                 },
             }
             .into()]
@@ -1136,4 +1654,119 @@ mod test {
             .into()]
         );
     }
+
+    #[tokio::test]
+    async fn test_set_holidays() {
+        let message = MockMessageText::new().text("/settings");
+        let mut db = MockDatabase::new();
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_insert_or_update_user_holidays()
+            .withf(|_, country| country == "NL")
+            .returning(|_, _| Ok(()));
+        let mut bot = mock_bot(db, message);
+        bot.dispatch().await;
+        bot.update(
+            MockCallbackQuery::new()
+                .data("settings::change_holidays")
+                .message(bot.get_responses().sent_messages[0].clone()),
+        );
+        bot.dispatch().await;
+        let markup = bot.get_responses().sent_messages[0]
+            .reply_markup()
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            markup.inline_keyboard.last().unwrap()[0].kind,
+            CallbackData("setholidays::country::none".to_string())
+        );
+        bot.update(
+            MockCallbackQuery::new()
+                .data("setholidays::country::NL")
+                .message(bot.get_responses().sent_messages[0].clone()),
+        );
+        bot.dispatch_and_check_last_text(
+            &TgResponse::ChosenHolidays("🇳🇱 Nederland".to_owned()).to_string(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_set_catch_up() {
+        let message = MockMessageText::new().text("/settings");
+        let mut db = MockDatabase::new();
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_insert_or_update_user_catch_up_policy()
+            .withf(|_, policy| policy == "skip")
+            .returning(|_, _| Ok(()));
+        let mut bot = mock_bot(db, message);
+        bot.dispatch().await;
+        bot.update(
+            MockCallbackQuery::new()
+                .data("settings::change_catch_up")
+                .message(bot.get_responses().sent_messages[0].clone()),
+        );
+        bot.dispatch().await;
+        let markup = bot.get_responses().sent_messages[0]
+            .reply_markup()
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            markup.inline_keyboard.last().unwrap()[0].kind,
+            CallbackData("setcatchup::policy::default".to_string())
+        );
+        bot.update(
+            MockCallbackQuery::new()
+                .data("setcatchup::policy::skip")
+                .message(bot.get_responses().sent_messages[0].clone()),
+        );
+        bot.dispatch_and_check_last_text(
+            &TgResponse::ChosenCatchUp("Skip the old ones".to_owned())
+                .to_string(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_set_dst() {
+        let message = MockMessageText::new().text("/settings");
+        let mut db = MockDatabase::new();
+        db.expect_get_user_language_name()
+            .returning(|_| Ok(Some(mock_language_name())));
+        db.expect_get_user_dst_policy()
+            .returning(|_| Ok(Some(("skip".to_owned(), "early".to_owned()))));
+        db.expect_insert_or_update_user_dst_policy()
+            .withf(|_, gap, overlap| gap == "skip" && overlap == "late")
+            .returning(|_, _, _| Ok(()));
+        let mut bot = mock_bot(db, message);
+        bot.dispatch().await;
+        bot.update(
+            MockCallbackQuery::new()
+                .data("settings::change_dst")
+                .message(bot.get_responses().sent_messages[0].clone()),
+        );
+        bot.dispatch().await;
+        let markup = bot.get_responses().sent_messages[0]
+            .reply_markup()
+            .cloned()
+            .unwrap();
+        assert_eq!(
+            markup.inline_keyboard[1][1].kind,
+            CallbackData("setdst::overlap::late".to_string())
+        );
+        bot.update(
+            MockCallbackQuery::new()
+                .data("setdst::overlap::late")
+                .message(bot.get_responses().sent_messages[0].clone()),
+        );
+        bot.dispatch_and_check_last_text(
+            &TgResponse::ChosenDst(
+                "Skipped times are skipped".to_owned(),
+                "Repeated times come the second time".to_owned(),
+            )
+            .to_string(),
+        )
+        .await;
+    }
 }
@@ -0,0 +1,237 @@
+#[cfg(not(test))]
+use crate::db::Database;
+#[cfg(test)]
+use crate::db::MockDatabase as Database;
+use crate::parsers::now_time;
+use chrono::{NaiveDateTime, TimeDelta};
+use chrono_tz::Tz;
+use teloxide::types::UserId;
+use teloxide::utils::markdown::escape;
+
+/// How late an occurrence can be sent before it counts as missed
+const MISSED_AFTER: TimeDelta = TimeDelta::minutes(1);
+/// At most this many missed occurrences are delivered one by one
+const MAX_LATE_MESSAGES: usize = 10;
+/// At most this many missed occurrences of a reminder are looked at
+pub(crate) const MAX_MISSED_OCCURRENCES: usize = 1000;
+
+/// What to do with the occurrences missed while the bot was down
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum CatchUpPolicy {
+    /// Deliver every missed occurrence with a note
+    Late,
+    /// Deliver one message for all the missed occurrences
+    Summary,
+    /// Drop the occurrences older than a threshold
+    Skip,
+}
+
+impl CatchUpPolicy {
+    pub(crate) const fn code(self) -> &'static str {
+        match self {
+            CatchUpPolicy::Late => "late",
+            CatchUpPolicy::Summary => "summary",
+            CatchUpPolicy::Skip => "skip",
+        }
+    }
+
+    pub(crate) fn name(self, lang: &str) -> String {
+        match self {
+            CatchUpPolicy::Late => t!("CatchUpLate", locale = lang),
+            CatchUpPolicy::Summary => t!("CatchUpSummary", locale = lang),
+            CatchUpPolicy::Skip => t!("CatchUpSkip", locale = lang),
+        }
+        .to_string()
+    }
+
+    pub(crate) fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "late" => Some(Self::Late),
+            "summary" => Some(Self::Summary),
+            "skip" => Some(Self::Skip),
+            _ => None,
+        }
+    }
+}
+
+pub const CATCH_UP_POLICIES: &[CatchUpPolicy] = &[
+    CatchUpPolicy::Late,
+    CatchUpPolicy::Summary,
+    CatchUpPolicy::Skip,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct CatchUp {
+    pub(crate) policy: CatchUpPolicy,
+    /// Age of the occurrences dropped by [`CatchUpPolicy::Skip`]
+    pub(crate) threshold: TimeDelta,
+}
+
+/// A message to send for some of the due occurrences of a reminder
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Delivery {
+    OnTime(NaiveDateTime),
+    Late(NaiveDateTime),
+    Summary {
+        count: usize,
+        first: NaiveDateTime,
+        last: NaiveDateTime,
+    },
+}
+
+impl Delivery {
+    /// Time of the occurrence the message is sent for
+    pub(crate) const fn time(&self) -> NaiveDateTime {
+        match *self {
+            Delivery::OnTime(time) | Delivery::Late(time) => time,
+            Delivery::Summary { last, .. } => last,
+        }
+    }
+
+    /// Markdown note to append to the reminder's message
+    pub(crate) fn note(&self, user_timezone: Tz, lang: &str) -> Option<String> {
+        let note = match *self {
+            Delivery::OnTime(_) => return None,
+            Delivery::Late(time) => t!(
+                "MissedAt",
+                locale = lang,
+                time = format_time(time, user_timezone)
+            ),
+            Delivery::Summary { count, first, last } => t!(
+                "MissedTimes",
+                locale = lang,
+                count = count,
+                from = format_time(first, user_timezone),
+                until = format_time(last, user_timezone)
+            ),
+        };
+        Some(escape(&note))
+    }
+}
+
+/// The time of a day in the user's timezone, with the date if it's not today
+fn format_time(time: NaiveDateTime, user_timezone: Tz) -> String {
+    let time = time.and_utc().with_timezone(&user_timezone);
+    let now = now_time().and_utc().with_timezone(&user_timezone);
+    if time.date_naive() == now.date_naive() {
+        time.format("%H:%M").to_string()
+    } else {
+        time.format("%d.%m %H:%M").to_string()
+    }
+}
+
+impl CatchUp {
+    /// Messages to send for the occurrences that were due by `now`
+    pub(crate) fn deliveries(
+        &self,
+        due: &[NaiveDateTime],
+        now: NaiveDateTime,
+    ) -> Vec<Delivery> {
+        let (mut missed, on_time): (Vec<_>, Vec<_>) =
+            due.iter().partition(|&&time| now - time > MISSED_AFTER);
+        if self.policy == CatchUpPolicy::Skip {
+            missed.retain(|&time| now - time <= self.threshold);
+        }
+        let mut deliveries = match (self.policy, missed.as_slice()) {
+            (CatchUpPolicy::Summary, &[first, .., last]) => {
+                vec![Delivery::Summary {
+                    count: missed.len(),
+                    first,
+                    last,
+                }]
+            }
+            _ => missed
+                .iter()
+                .skip(missed.len().saturating_sub(MAX_LATE_MESSAGES))
+                .map(|&time| Delivery::Late(time))
+                .collect(),
+        };
+        deliveries.extend(on_time.into_iter().map(Delivery::OnTime));
+        deliveries
+    }
+}
+
+pub(crate) async fn get_user_catch_up(
+    db: &Database,
+    user_id: UserId,
+    default: CatchUp,
+) -> CatchUp {
+    let policy = db
+        .get_user_catch_up_policy(user_id.0 as i64)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|code| CatchUpPolicy::from_code(&code));
+    CatchUp {
+        policy: policy.unwrap_or(default.policy),
+        ..default
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+    use test_case::test_case;
+
+    fn ts(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 2, 2)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    fn catch_up(policy: CatchUpPolicy) -> CatchUp {
+        CatchUp {
+            policy,
+            threshold: TimeDelta::hours(1),
+        }
+    }
+
+    #[test_case(CatchUpPolicy::Late ; "late")]
+    #[test_case(CatchUpPolicy::Summary ; "summary")]
+    #[test_case(CatchUpPolicy::Skip ; "skip")]
+    fn test_on_time(policy: CatchUpPolicy) {
+        assert_eq!(
+            catch_up(policy).deliveries(&[ts(10, 0)], ts(10, 0)),
+            vec![Delivery::OnTime(ts(10, 0))]
+        );
+    }
+
+    #[test_case(CatchUpPolicy::Late, vec![Delivery::Late(ts(8, 0)), Delivery::Late(ts(9, 0)), Delivery::Late(ts(10, 0))] ; "late")]
+    #[test_case(CatchUpPolicy::Summary, vec![Delivery::Summary { count: 3, first: ts(8, 0), last: ts(10, 0) }] ; "summary")]
+    #[test_case(CatchUpPolicy::Skip, vec![Delivery::Late(ts(10, 0))] ; "skip")]
+    fn test_missed(policy: CatchUpPolicy, expected: Vec<Delivery>) {
+        assert_eq!(
+            catch_up(policy)
+                .deliveries(&[ts(8, 0), ts(9, 0), ts(10, 0)], ts(10, 30)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_summary_of_one() {
+        assert_eq!(
+            catch_up(CatchUpPolicy::Summary)
+                .deliveries(&[ts(8, 0), ts(10, 30)], ts(10, 30)),
+            vec![Delivery::Late(ts(8, 0)), Delivery::OnTime(ts(10, 30))]
+        );
+    }
+
+    #[test]
+    fn test_skip_all() {
+        assert_eq!(
+            catch_up(CatchUpPolicy::Skip).deliveries(&[ts(8, 0)], ts(10, 30)),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_late_messages_limit() {
+        let due: Vec<_> = (0..30).map(|m| ts(9, m)).collect();
+        let deliveries =
+            catch_up(CatchUpPolicy::Late).deliveries(&due, ts(10, 0));
+        assert_eq!(deliveries.len(), MAX_LATE_MESSAGES);
+        assert_eq!(deliveries.last(), Some(&Delivery::Late(ts(9, 29))));
+    }
+}
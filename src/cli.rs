@@ -3,6 +3,8 @@ use std::{ffi::OsString, path::PathBuf};
 use clap::Parser;
 use directories::BaseDirs;
 
+use crate::catch_up::CatchUpPolicy;
+
 lazy_static::lazy_static! {
     pub(crate) static ref CLI: Cli = parse_args();
 }
@@ -30,6 +32,22 @@ pub(crate) struct Cli {
         default_value = "1"
     )]
     pub(crate) sqlite_max_connections: u32,
+    #[arg(
+        long,
+        env = "REMINDEE_CATCH_UP",
+        value_enum,
+        help = "What to do with the reminders missed while the bot was down, unless a user chose otherwise",
+        default_value = "summary"
+    )]
+    pub(crate) catch_up: CatchUpPolicy,
+    #[arg(
+        long,
+        env = "REMINDEE_CATCH_UP_THRESHOLD",
+        value_name = "MINUTES",
+        help = "Age of the missed reminders dropped by the skip policy",
+        default_value = "60"
+    )]
+    pub(crate) catch_up_threshold: u32,
 }
 
 pub(crate) fn parse_args() -> Cli {
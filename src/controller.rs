@@ -1,21 +1,27 @@
 use std::future::Future;
 use std::sync::Arc;
 
+use crate::catch_up::{CatchUpPolicy, CATCH_UP_POLICIES};
 use crate::db;
 #[cfg(not(test))]
 use crate::db::Database;
 #[cfg(test)]
 use crate::db::MockDatabase as Database;
 use crate::err::Error;
+use crate::format::format_upcoming_times;
 use crate::lang::get_user_language;
 use crate::lang::Language;
 use crate::parsers;
+use crate::sun::get_user_location;
 use crate::tg;
-use crate::tz;
+use crate::tz::{self, get_user_calendar, get_user_dst_policy};
 
 use crate::entity::{cron_reminder, reminder};
 use crate::generic_reminder::GenericReminder;
+use chrono::NaiveDateTime;
 use chrono_tz::Tz;
+use remindee_parser::ParseError;
+use remindee_pattern::{Calendar, Country, DstGap, DstOverlap, COUNTRIES};
 use sea_orm::IntoActiveModel;
 use teloxide::prelude::*;
 use teloxide::types::MessageId;
@@ -25,6 +31,9 @@ use teloxide::types::{
 use teloxide::RequestError;
 use tg::TgResponse;
 
+/// Number of the upcoming times to preview
+const UPCOMING_TIMES_COUNT: usize = 10;
+
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub(crate) enum EditMode {
     TimePattern,
@@ -63,6 +72,25 @@ pub(crate) enum ActiveReminder {
     CronReminder(cron_reminder::ActiveModel),
 }
 
+impl ActiveReminder {
+    fn upcoming_times(
+        &self,
+        user_tz: Tz,
+        calendar: Calendar,
+    ) -> Vec<NaiveDateTime> {
+        match self {
+            Self::Reminder(reminder) => {
+                reminder.upcoming_times(user_tz, calendar, UPCOMING_TIMES_COUNT)
+            }
+            Self::CronReminder(cron_reminder) => cron_reminder.upcoming_times(
+                user_tz,
+                calendar,
+                UPCOMING_TIMES_COUNT,
+            ),
+        }
+    }
+}
+
 trait ReminderModel {
     type R: GenericReminder;
     fn into_active(self) -> Self::R;
@@ -151,6 +179,23 @@ impl TgMessageController {
         .await
     }
 
+    /// Reply to the user's message with a markup
+    async fn reply_with_markup(
+        &self,
+        response: TgResponse,
+        markup: InlineKeyboardMarkup,
+    ) -> Result<Message, RequestError> {
+        let lang = self.user_lang().await;
+        tg::send_silent_reply_markup(
+            &response.to_string_lang(lang.code()),
+            markup,
+            &self.bot,
+            self.chat_id,
+            self.msg_id,
+        )
+        .await
+    }
+
     pub(crate) async fn start(&self) -> Result<(), RequestError> {
         self.reply(TgResponse::Hello).await.map(|_| ())
     }
@@ -210,6 +255,39 @@ impl TgMessageController {
         .await
     }
 
+    pub(crate) async fn choose_holidays(&self) -> Result<(), RequestError> {
+        let lang = self.user_lang().await;
+        tg::send_markup(
+            &TgResponse::SelectHolidays.to_string_lang(lang.code()),
+            self.get_markup_for_holidays(lang),
+            &self.bot,
+            self.chat_id,
+        )
+        .await
+    }
+
+    pub(crate) async fn choose_catch_up(&self) -> Result<(), RequestError> {
+        let lang = self.user_lang().await;
+        tg::send_markup(
+            &TgResponse::SelectCatchUp.to_string_lang(lang.code()),
+            self.get_markup_for_catch_up(lang),
+            &self.bot,
+            self.chat_id,
+        )
+        .await
+    }
+
+    pub(crate) async fn choose_dst(&self) -> Result<(), RequestError> {
+        let lang = self.user_lang().await;
+        tg::send_markup(
+            &TgResponse::SelectDst.to_string_lang(lang.code()),
+            self.get_markup_for_dst(lang),
+            &self.bot,
+            self.chat_id,
+        )
+        .await
+    }
+
     pub(crate) async fn choose_settings(&self) -> Result<(), RequestError> {
         let lang = self.user_lang().await;
         tg::send_markup(
@@ -331,8 +409,8 @@ impl TgMessageController {
         &self,
         text: &str,
         tz: Tz,
-    ) -> Option<ActiveReminder> {
-        parsers::parse_cron_reminder(
+    ) -> Result<Option<ActiveReminder>, ParseError> {
+        if let Some(cron_reminder) = parsers::parse_cron_reminder(
             text,
             self.chat_id.0,
             self.user_id.0,
@@ -340,16 +418,23 @@ impl TgMessageController {
             tz,
         )
         .await
-        .map(ActiveReminder::CronReminder)
-        .or(parsers::parse_reminder(
+        {
+            return Ok(Some(ActiveReminder::CronReminder(cron_reminder)));
+        }
+        let settings = parsers::UserSettings {
+            timezone: tz,
+            language: self.user_lang().await,
+            calendar: get_user_calendar(&self.db, self.user_id).await,
+        };
+        parsers::parse_reminder(
             text,
             self.chat_id.0,
             self.user_id.0,
             self.msg_id.0,
-            tz,
+            &settings,
         )
         .await
-        .map(ActiveReminder::Reminder))
+        .map(|reminder| reminder.map(ActiveReminder::Reminder))
     }
 
     /// Try to parse user's message into a one-time or periodic reminder and set it
@@ -359,7 +444,7 @@ impl TgMessageController {
         user_tz: Tz,
     ) -> (Option<ActiveReminder>, Option<TgResponse>) {
         match self.parse_reminder(text, user_tz).await {
-            Some(ActiveReminder::Reminder(reminder)) => {
+            Ok(Some(ActiveReminder::Reminder(reminder))) => {
                 match self.db.insert_reminder(reminder.clone()).await {
                     Ok(reminder) => {
                         let rem_str = reminder
@@ -376,7 +461,7 @@ impl TgMessageController {
                     }
                 }
             }
-            Some(ActiveReminder::CronReminder(cron_reminder)) => {
+            Ok(Some(ActiveReminder::CronReminder(cron_reminder))) => {
                 match self.db.insert_cron_reminder(cron_reminder.clone()).await
                 {
                     Ok(cron_reminder) => {
@@ -393,9 +478,29 @@ impl TgMessageController {
                     }
                 }
             }
-            None => {
+            Ok(None) => {
+                if self.user_id.0 == self.chat_id.0 as u64 {
+                    let lang = self.user_lang().await;
+                    // sun times can't be computed without a location
+                    if parsers::uses_sun_times(text, lang)
+                        && get_user_location(&self.db, self.user_id)
+                            .await
+                            .is_none()
+                    {
+                        (None, Some(TgResponse::LocationRequired))
+                    } else {
+                        (None, Some(TgResponse::IncorrectRequest))
+                    }
+                } else {
+                    (None, None)
+                }
+            }
+            Err(err) => {
                 if self.user_id.0 == self.chat_id.0 as u64 {
-                    (None, Some(TgResponse::IncorrectRequest))
+                    (
+                        None,
+                        Some(TgResponse::InvalidReminder(text.to_owned(), err)),
+                    )
                 } else {
                     (None, None)
                 }
@@ -561,16 +666,140 @@ impl TgMessageController {
         InlineKeyboardMarkup::default().append_row(row)
     }
 
+    pub(crate) fn get_markup_for_holidays(
+        &self,
+        lang: Language,
+    ) -> InlineKeyboardMarkup {
+        let mut markup = InlineKeyboardMarkup::default();
+        for chunk in COUNTRIES.chunks(2) {
+            markup = markup.append_row(
+                chunk
+                    .iter()
+                    .map(|country| {
+                        InlineKeyboardButton::new(
+                            country.name(),
+                            InlineKeyboardButtonKind::CallbackData(format!(
+                                "setholidays::country::{}",
+                                country.code()
+                            )),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+        markup.append_row(vec![InlineKeyboardButton::new(
+            t!("NoHolidays", locale = lang.code()),
+            InlineKeyboardButtonKind::CallbackData(
+                "setholidays::country::none".into(),
+            ),
+        )])
+    }
+
+    pub(crate) fn get_markup_for_catch_up(
+        &self,
+        lang: Language,
+    ) -> InlineKeyboardMarkup {
+        let mut markup = InlineKeyboardMarkup::default();
+        for policy in CATCH_UP_POLICIES {
+            markup = markup.append_row(vec![InlineKeyboardButton::new(
+                policy.name(lang.code()),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "setcatchup::policy::{}",
+                    policy.code()
+                )),
+            )]);
+        }
+        markup.append_row(vec![InlineKeyboardButton::new(
+            t!("DefaultCatchUp", locale = lang.code()),
+            InlineKeyboardButtonKind::CallbackData(
+                "setcatchup::policy::default".into(),
+            ),
+        )])
+    }
+
+    pub(crate) fn get_markup_for_dst(
+        &self,
+        lang: Language,
+    ) -> InlineKeyboardMarkup {
+        let button = |key: &str, data: &str| {
+            InlineKeyboardButton::new(
+                t!(key, locale = lang.code()),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "setdst::{data}"
+                )),
+            )
+        };
+        InlineKeyboardMarkup::default()
+            .append_row(vec![
+                button("DstGapShiftForward", "gap::fwd"),
+                button("DstGapSkip", "gap::skip"),
+            ])
+            .append_row(vec![
+                button("DstOverlapEarlier", "overlap::early"),
+                button("DstOverlapLater", "overlap::late"),
+            ])
+    }
+
     pub(crate) async fn get_markup_for_settings(&self) -> InlineKeyboardMarkup {
         let lang = self.user_lang().await;
-        InlineKeyboardMarkup::default().append_row(vec![
-            InlineKeyboardButton::new(
+        InlineKeyboardMarkup::default()
+            .append_row(vec![InlineKeyboardButton::new(
                 t!("ChangeLanguage", locale = lang.code()),
                 InlineKeyboardButtonKind::CallbackData(
                     "settings::change_lang".into(),
                 ),
-            ),
-        ])
+            )])
+            .append_row(vec![InlineKeyboardButton::new(
+                t!("ChangeHolidays", locale = lang.code()),
+                InlineKeyboardButtonKind::CallbackData(
+                    "settings::change_holidays".into(),
+                ),
+            )])
+            .append_row(vec![InlineKeyboardButton::new(
+                t!("ChangeCatchUp", locale = lang.code()),
+                InlineKeyboardButtonKind::CallbackData(
+                    "settings::change_catch_up".into(),
+                ),
+            )])
+            .append_row(vec![InlineKeyboardButton::new(
+                t!("ChangeDst", locale = lang.code()),
+                InlineKeyboardButtonKind::CallbackData(
+                    "settings::change_dst".into(),
+                ),
+            )])
+    }
+
+    /// Buttons under the reply to a new reminder: one to show its
+    /// upcoming times if it's set, and one to set the reminder with
+    /// the misspelled keywords corrected, which only its author can press
+    async fn get_markup_for_new_reminder(
+        &self,
+        text: &str,
+        is_set: bool,
+    ) -> Option<InlineKeyboardMarkup> {
+        let lang = self.user_lang().await;
+        let mut markup = InlineKeyboardMarkup::default();
+        if is_set {
+            markup = markup.append_row(vec![InlineKeyboardButton::new(
+                t!(
+                    "ShowUpcomingTimes",
+                    locale = lang.code(),
+                    count = UPCOMING_TIMES_COUNT
+                ),
+                InlineKeyboardButtonKind::CallbackData(
+                    "preview::upcoming".into(),
+                ),
+            )]);
+        }
+        if let Some(corrected) = parsers::suggest_correction(text, lang) {
+            markup = markup.append_row(vec![InlineKeyboardButton::new(
+                t!("DidYouMean", locale = lang.code(), text = corrected),
+                InlineKeyboardButtonKind::CallbackData(
+                    "didyoumean::apply".into(),
+                ),
+            )]);
+        }
+        (!markup.inline_keyboard.is_empty()).then_some(markup)
     }
 
     async fn get_markup_for_reminders_page_alteration(
@@ -870,6 +1099,26 @@ impl TgMessageController {
         Ok(())
     }
 
+    /// Show the upcoming times of a reminder without setting it
+    pub(crate) async fn preview(
+        &self,
+        text: &str,
+        user_tz: Tz,
+    ) -> Result<(), RequestError> {
+        let calendar = get_user_calendar(&self.db, self.user_id).await;
+        let response = match self.parse_reminder(text, user_tz).await {
+            Ok(Some(reminder)) => {
+                TgResponse::UpcomingTimes(format_upcoming_times(
+                    &reminder.upcoming_times(user_tz, calendar),
+                    user_tz,
+                ))
+            }
+            Ok(None) => TgResponse::IncorrectRequest,
+            Err(err) => TgResponse::InvalidReminder(text.to_owned(), err),
+        };
+        self.reply(response).await.map(|_| ())
+    }
+
     pub(crate) async fn set_new_reminder(
         &self,
         text: &str,
@@ -877,7 +1126,15 @@ impl TgMessageController {
     ) -> Result<(), Error> {
         let (reminder, response) = self.set_reminder(text, user_tz).await;
         if let Some(response) = response {
-            let reply = self.reply(response).await?;
+            let reply = match self
+                .get_markup_for_new_reminder(text, reminder.is_some())
+                .await
+            {
+                Some(markup) => {
+                    self.reply_with_markup(response, markup).await?
+                }
+                None => self.reply(response).await?,
+            };
             if let Some(ref reminder) = reminder {
                 self.update_reply_link(reminder, &reply, None).await?;
             }
@@ -927,6 +1184,29 @@ impl TgMessageController {
         self.reply(response).await.map(|_| ())
     }
 
+    /// Remember the location for the sun times and set the timezone there
+    pub(crate) async fn set_location(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<(), RequestError> {
+        if let Err(err) = self
+            .db
+            .insert_or_update_user_location(
+                self.user_id.0 as i64,
+                latitude,
+                longitude,
+            )
+            .await
+        {
+            log::error!("{}", err);
+        }
+        self.set_timezone(tz::get_timezone_name_of_location(
+            longitude, latitude,
+        ))
+        .await
+    }
+
     pub(crate) async fn set_language(
         &self,
         lang_code: &str,
@@ -946,6 +1226,117 @@ impl TgMessageController {
         self.reply(response).await.map(|_| ())
     }
 
+    pub(crate) async fn set_holidays(
+        &self,
+        country_code: &str,
+    ) -> Result<(), RequestError> {
+        let user_id = self.user_id.0 as i64;
+        let country = Country::from_code(country_code);
+        let result = match country {
+            Some(country) => {
+                self.db
+                    .insert_or_update_user_holidays(user_id, country.code())
+                    .await
+            }
+            None => self.db.delete_user_holidays(user_id).await,
+        };
+        let response = match result {
+            Ok(()) => country
+                .map(|country| {
+                    TgResponse::ChosenHolidays(country.name().to_owned())
+                })
+                .unwrap_or(TgResponse::ChosenNoHolidays),
+            Err(err) => {
+                log::error!("{}", err);
+                TgResponse::FailedSetHolidays
+            }
+        };
+        self.reply(response).await.map(|_| ())
+    }
+
+    pub(crate) async fn set_catch_up(
+        &self,
+        policy_code: &str,
+    ) -> Result<(), RequestError> {
+        let user_id = self.user_id.0 as i64;
+        let policy = CatchUpPolicy::from_code(policy_code);
+        let result = match policy {
+            Some(policy) => {
+                self.db
+                    .insert_or_update_user_catch_up_policy(
+                        user_id,
+                        policy.code(),
+                    )
+                    .await
+            }
+            None => self.db.delete_user_catch_up_policy(user_id).await,
+        };
+        let lang = self.user_lang().await;
+        let response = match result {
+            Ok(()) => policy
+                .map(|policy| {
+                    TgResponse::ChosenCatchUp(policy.name(lang.code()))
+                })
+                .unwrap_or(TgResponse::ChosenDefaultCatchUp),
+            Err(err) => {
+                log::error!("{}", err);
+                TgResponse::FailedSetCatchUp
+            }
+        };
+        self.reply(response).await.map(|_| ())
+    }
+
+    /// Change one of the DST settings given as `gap::<code>`
+    /// or `overlap::<code>`, keeping the other one
+    pub(crate) async fn set_dst(
+        &self,
+        setting: &str,
+    ) -> Result<(), RequestError> {
+        let mut dst = get_user_dst_policy(&self.db, self.user_id).await;
+        let changed = match setting.split_once("::") {
+            Some(("gap", code)) => {
+                DstGap::from_code(code).map(|gap| dst.gap = gap).is_some()
+            }
+            Some(("overlap", code)) => DstOverlap::from_code(code)
+                .map(|overlap| dst.overlap = overlap)
+                .is_some(),
+            _ => false,
+        };
+        if !changed {
+            return self.reply(TgResponse::IncorrectRequest).await.map(|_| ());
+        }
+        let lang = self.user_lang().await;
+        let response = match self
+            .db
+            .insert_or_update_user_dst_policy(
+                self.user_id.0 as i64,
+                dst.gap.code(),
+                dst.overlap.code(),
+            )
+            .await
+        {
+            Ok(()) => {
+                let gap = match dst.gap {
+                    DstGap::ShiftForward => "DstGapShiftForward",
+                    DstGap::Skip => "DstGapSkip",
+                };
+                let overlap = match dst.overlap {
+                    DstOverlap::Earlier => "DstOverlapEarlier",
+                    DstOverlap::Later => "DstOverlapLater",
+                };
+                TgResponse::ChosenDst(
+                    t!(gap, locale = lang.code()).to_string(),
+                    t!(overlap, locale = lang.code()).to_string(),
+                )
+            }
+            Err(err) => {
+                log::error!("{}", err);
+                TgResponse::FailedSetDst
+            }
+        };
+        self.reply(response).await.map(|_| ())
+    }
+
     async fn get_reminder_by_msg_id(
         &self,
         msg_id: MessageId,
@@ -1083,6 +1474,94 @@ impl TgCallbackController {
             .map(|_| ())
     }
 
+    /// Show the upcoming times of the reminder the message is a reply to
+    pub(crate) async fn show_upcoming_times(
+        &self,
+        user_tz: Tz,
+    ) -> Result<(), Error> {
+        let response = match self
+            .msg_ctl
+            .get_reminder_by_reply_id(self.msg_ctl.msg_id)
+            .await?
+        {
+            Some(reminder) => {
+                let reminder = match reminder {
+                    Reminder::Reminder(reminder) => {
+                        ActiveReminder::Reminder(reminder.into_active_model())
+                    }
+                    Reminder::CronReminder(cron_reminder) => {
+                        ActiveReminder::CronReminder(
+                            cron_reminder.into_active_model(),
+                        )
+                    }
+                };
+                let calendar =
+                    get_user_calendar(&self.msg_ctl.db, self.msg_ctl.user_id)
+                        .await;
+                TgResponse::UpcomingTimes(format_upcoming_times(
+                    &reminder.upcoming_times(user_tz, calendar),
+                    user_tz,
+                ))
+            }
+            None => TgResponse::ReminderNotFound,
+        };
+        self.answer_callback_query(response)
+            .await
+            .map_err(From::from)
+    }
+
+    /// Set the reminder from the corrected text of the user's message,
+    /// replacing the one already set from the misspelled text
+    pub(crate) async fn apply_correction(
+        &self,
+        original: &Message,
+        user_tz: Tz,
+    ) -> Result<(), Error> {
+        let lang = self.msg_ctl.user_lang().await;
+        let corrected = original
+            .text()
+            .filter(|_| {
+                original.from.as_ref().map(|user| user.id)
+                    == Some(self.msg_ctl.user_id)
+            })
+            // the reminder of a command like /set is its argument
+            .map(|text| match text.strip_prefix('/') {
+                Some(command) => command
+                    .split_once(char::is_whitespace)
+                    .map_or("", |(_, argument)| argument.trim_start()),
+                None => text,
+            })
+            .and_then(|text| parsers::suggest_correction(text, lang));
+        let Some(corrected) = corrected else {
+            self.answer_callback_query(TgResponse::IncorrectRequest)
+                .await?;
+            return Ok(());
+        };
+        tg::edit_markup(
+            InlineKeyboardMarkup::default(),
+            &self.msg_ctl.bot,
+            self.msg_ctl.msg_id,
+            self.msg_ctl.chat_id,
+        )
+        .await?;
+        // act as if the user has edited the message
+        let ctl = TgMessageController::new(
+            self.msg_ctl.db.clone(),
+            self.msg_ctl.bot.clone(),
+            self.msg_ctl.chat_id,
+            self.msg_ctl.user_id,
+            original.id,
+            None,
+        );
+        if ctl.get_reminder_by_msg_id(original.id).await?.is_some() {
+            ctl.edit_reminder_from_edited_message(&corrected, user_tz)
+                .await?;
+        } else {
+            ctl.set_new_reminder(&corrected, user_tz).await?;
+        }
+        self.acknowledge_callback().await.map_err(From::from)
+    }
+
     pub(crate) async fn set_timezone(
         &self,
         tz_name: &str,
@@ -1099,6 +1578,30 @@ impl TgCallbackController {
         self.acknowledge_callback().await
     }
 
+    pub(crate) async fn set_holidays(
+        &self,
+        country_code: &str,
+    ) -> Result<(), RequestError> {
+        self.msg_ctl.set_holidays(country_code).await?;
+        self.acknowledge_callback().await
+    }
+
+    pub(crate) async fn set_catch_up(
+        &self,
+        policy_code: &str,
+    ) -> Result<(), RequestError> {
+        self.msg_ctl.set_catch_up(policy_code).await?;
+        self.acknowledge_callback().await
+    }
+
+    pub(crate) async fn set_dst(
+        &self,
+        setting: &str,
+    ) -> Result<(), RequestError> {
+        self.msg_ctl.set_dst(setting).await?;
+        self.acknowledge_callback().await
+    }
+
     pub(crate) async fn delete_reminder(
         &self,
         rem_id: i64,
@@ -1,7 +1,10 @@
 use std::path::Path;
 
 use crate::cli::CLI;
-use crate::entity::{cron_reminder, reminder, user_language, user_timezone};
+use crate::entity::{
+    cron_reminder, reminder, user_catch_up, user_dst, user_holidays,
+    user_language, user_location, user_timezone,
+};
 use crate::generic_reminder;
 use crate::migration::{DbErr, Migrator, MigratorTrait};
 use crate::parsers::now_time;
@@ -256,6 +259,177 @@ impl Database {
         Ok(())
     }
 
+    pub(crate) async fn get_user_holidays_country(
+        &self,
+        user_id: i64,
+    ) -> Result<Option<String>, Error> {
+        Ok(user_holidays::Entity::find_by_id(user_id)
+            .one(&self.pool)
+            .await?
+            .map(|x| x.country))
+    }
+
+    async fn insert_user_holidays_country(
+        &self,
+        user_id: i64,
+        country: &str,
+    ) -> Result<(), Error> {
+        defer!(self.notify.notify_one());
+        user_holidays::Entity::insert(user_holidays::ActiveModel {
+            user_id: Set(user_id),
+            country: Set(country.to_string()),
+        })
+        .exec(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn insert_or_update_user_holidays(
+        &self,
+        user_id: i64,
+        country: &str,
+    ) -> Result<(), Error> {
+        if let Some(mut holidays_act) =
+            user_holidays::Entity::find_by_id(user_id)
+                .one(&self.pool)
+                .await?
+                .map(Into::<user_holidays::ActiveModel>::into)
+        {
+            holidays_act.country = Set(country.to_string());
+            holidays_act.update(&self.pool).await?;
+        } else {
+            self.insert_user_holidays_country(user_id, country).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn delete_user_holidays(
+        &self,
+        user_id: i64,
+    ) -> Result<(), Error> {
+        user_holidays::Entity::delete_by_id(user_id)
+            .exec(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn get_user_catch_up_policy(
+        &self,
+        user_id: i64,
+    ) -> Result<Option<String>, Error> {
+        Ok(user_catch_up::Entity::find_by_id(user_id)
+            .one(&self.pool)
+            .await?
+            .map(|x| x.policy))
+    }
+
+    pub(crate) async fn insert_or_update_user_catch_up_policy(
+        &self,
+        user_id: i64,
+        policy: &str,
+    ) -> Result<(), Error> {
+        if let Some(mut catch_up_act) =
+            user_catch_up::Entity::find_by_id(user_id)
+                .one(&self.pool)
+                .await?
+                .map(Into::<user_catch_up::ActiveModel>::into)
+        {
+            catch_up_act.policy = Set(policy.to_string());
+            catch_up_act.update(&self.pool).await?;
+        } else {
+            user_catch_up::Entity::insert(user_catch_up::ActiveModel {
+                user_id: Set(user_id),
+                policy: Set(policy.to_string()),
+            })
+            .exec(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn delete_user_catch_up_policy(
+        &self,
+        user_id: i64,
+    ) -> Result<(), Error> {
+        user_catch_up::Entity::delete_by_id(user_id)
+            .exec(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn get_user_dst_policy(
+        &self,
+        user_id: i64,
+    ) -> Result<Option<(String, String)>, Error> {
+        Ok(user_dst::Entity::find_by_id(user_id)
+            .one(&self.pool)
+            .await?
+            .map(|x| (x.gap, x.overlap)))
+    }
+
+    pub(crate) async fn insert_or_update_user_dst_policy(
+        &self,
+        user_id: i64,
+        gap: &str,
+        overlap: &str,
+    ) -> Result<(), Error> {
+        if let Some(mut dst_act) = user_dst::Entity::find_by_id(user_id)
+            .one(&self.pool)
+            .await?
+            .map(Into::<user_dst::ActiveModel>::into)
+        {
+            dst_act.gap = Set(gap.to_string());
+            dst_act.overlap = Set(overlap.to_string());
+            dst_act.update(&self.pool).await?;
+        } else {
+            user_dst::Entity::insert(user_dst::ActiveModel {
+                user_id: Set(user_id),
+                gap: Set(gap.to_string()),
+                overlap: Set(overlap.to_string()),
+            })
+            .exec(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn get_user_location(
+        &self,
+        user_id: i64,
+    ) -> Result<Option<(f64, f64)>, Error> {
+        Ok(user_location::Entity::find_by_id(user_id)
+            .one(&self.pool)
+            .await?
+            .map(|x| (x.latitude, x.longitude)))
+    }
+
+    pub(crate) async fn insert_or_update_user_location(
+        &self,
+        user_id: i64,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<(), Error> {
+        if let Some(mut location_act) =
+            user_location::Entity::find_by_id(user_id)
+                .one(&self.pool)
+                .await?
+                .map(Into::<user_location::ActiveModel>::into)
+        {
+            location_act.latitude = Set(latitude);
+            location_act.longitude = Set(longitude);
+            location_act.update(&self.pool).await?;
+        } else {
+            user_location::Entity::insert(user_location::ActiveModel {
+                user_id: Set(user_id),
+                latitude: Set(latitude),
+                longitude: Set(longitude),
+            })
+            .exec(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
     pub(crate) async fn get_cron_reminder(
         &self,
         id: i64,
@@ -4,5 +4,9 @@ pub mod prelude;
 
 pub mod cron_reminder;
 pub mod reminder;
+pub mod user_catch_up;
+pub mod user_dst;
+pub mod user_holidays;
 pub mod user_language;
+pub mod user_location;
 pub mod user_timezone;
@@ -3,5 +3,9 @@
 
 pub use super::cron_reminder::Entity as CronReminder;
 pub use super::reminder::Entity as Reminder;
+pub use super::user_catch_up::Entity as UserCatchUp;
+pub use super::user_dst::Entity as UserDst;
+pub use super::user_holidays::Entity as UserHolidays;
 pub use super::user_language::Entity as UserLanguage;
+pub use super::user_location::Entity as UserLocation;
 pub use super::user_timezone::Entity as UserTimezone;
@@ -1,5 +1,6 @@
 use crate::entity::cron_reminder;
 use crate::generic_reminder::GenericReminder;
+use chrono::{NaiveDateTime, TimeZone};
 use chrono_tz::Tz;
 use sea_orm::{ActiveModelTrait, IntoActiveModel};
 
@@ -35,3 +36,21 @@ pub(crate) fn format_cron_reminder(
         None => formatted_reminder,
     }
 }
+
+/// List the times in the user's timezone, with its abbreviation
+/// to tell the daylight saving time shifts
+pub(crate) fn format_upcoming_times(
+    times: &[NaiveDateTime],
+    user_timezone: Tz,
+) -> String {
+    times
+        .iter()
+        .map(|time| {
+            user_timezone
+                .from_utc_datetime(time)
+                .format("%d.%m.%Y %H:%M %Z")
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
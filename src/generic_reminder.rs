@@ -1,8 +1,9 @@
 use crate::entity::{cron_reminder, reminder};
 use crate::parsers::now_time;
-use crate::serializers::Pattern;
 use chrono::prelude::*;
 use chrono_tz::Tz;
+use cron_parser::parse as parse_cron;
+use remindee_pattern::{Calendar, Pattern, Tz as PatternTz};
 use serde_json::from_str;
 use std::cmp::Ord;
 use std::cmp::Ordering;
@@ -10,6 +11,20 @@ use teloxide::types::ChatId;
 use teloxide::types::UserId;
 use teloxide::utils::markdown::{bold, escape};
 
+/// The local time with the date if it's not today
+/// and the year if it's not this year
+fn format_local_time(time: NaiveDateTime, now: NaiveDateTime) -> String {
+    let mut s = String::new();
+    if time.date() != now.date() {
+        s += &format!("{:02}.{:02}", time.day(), time.month());
+        if time.year() != now.year() {
+            s += &format!(".{}", time.year())
+        }
+        s += " "
+    }
+    s + &format!("{:02}:{:02}", time.hour(), time.minute())
+}
+
 /// Interface to grab reminders of different types together
 /// to format, display, sort or get attributes
 pub(crate) trait GenericReminder {
@@ -30,17 +45,12 @@ pub(crate) trait GenericReminder {
     }
     fn to_unescaped_string(&self, user_timezone: Tz) -> String;
     fn serialize_time_unescaped(&self, user_timezone: Tz) -> String {
-        let time = user_timezone.from_utc_datetime(&self.get_time());
-        let now = user_timezone.from_utc_datetime(&now_time());
-        let mut s = String::new();
-        if time.date_naive() != now.date_naive() {
-            s += &format!("{:02}.{:02}", time.day(), time.month());
-            if time.year() != now.year() {
-                s += &format!(".{}", time.year())
-            }
-            s += " "
-        }
-        s + &format!("{:02}:{:02}", time.hour(), time.minute())
+        format_local_time(
+            user_timezone
+                .from_utc_datetime(&self.get_time())
+                .naive_local(),
+            user_timezone.from_utc_datetime(&now_time()).naive_local(),
+        )
     }
     fn serialize_time(&self, user_timezone: Tz) -> String {
         escape(&self.serialize_time_unescaped(user_timezone))
@@ -52,6 +62,35 @@ pub(crate) trait GenericReminder {
         chat_id.is_group() || chat_id.is_channel_or_supergroup()
     }
     fn is_paused(&self) -> bool;
+    /// Times of the next occurrences starting with the scheduled one
+    fn upcoming_times(
+        &self,
+        user_timezone: Tz,
+        calendar: Calendar,
+        count: usize,
+    ) -> Vec<NaiveDateTime>;
+}
+
+impl reminder::ActiveModel {
+    /// Describe the pattern along with its timezone
+    /// if it differs from the user's one
+    fn pattern_unescaped_string(&self, user_timezone: Tz) -> Option<String> {
+        let pattern: Pattern =
+            from_str(&self.pattern.clone().unwrap()?).unwrap();
+        let s = pattern.to_string();
+        let timezone = pattern.timezone();
+        if timezone == PatternTz::Named(user_timezone) {
+            return (!s.is_empty()).then_some(s);
+        }
+        let s = match s.as_str() {
+            "" => format_local_time(
+                timezone.to_local(&self.get_time()),
+                timezone.to_local(&now_time()),
+            ),
+            _ => s,
+        };
+        Some(format!("{s} {timezone}"))
+    }
 }
 
 impl GenericReminder for reminder::ActiveModel {
@@ -73,14 +112,8 @@ impl GenericReminder for reminder::ActiveModel {
             self.serialize_time_unescaped(user_timezone),
             self.desc.clone().unwrap(),
         );
-        let s = match self.pattern.clone().unwrap() {
-            Some(ref s) => {
-                let pattern: Pattern = from_str(s).unwrap();
-                match pattern.to_string().as_str() {
-                    "" => main_part,
-                    s => format!(r"{main_part} [{s}]"),
-                }
-            }
+        let s = match self.pattern_unescaped_string(user_timezone) {
+            Some(s) => format!(r"{main_part} [{s}]"),
             None => main_part,
         };
         if self.paused.clone().unwrap() {
@@ -96,14 +129,8 @@ impl GenericReminder for reminder::ActiveModel {
             self.serialize_time(user_timezone),
             bold(&escape(&self.desc.clone().unwrap())),
         );
-        let s = match self.pattern.clone().unwrap() {
-            Some(ref s) => {
-                let pattern: Pattern = from_str(s).unwrap();
-                match pattern.to_string().as_str() {
-                    "" => main_part,
-                    s => format!(r"{} \[{}\]", main_part, escape(s)),
-                }
-            }
+        let s = match self.pattern_unescaped_string(user_timezone) {
+            Some(s) => format!(r"{} \[{}\]", main_part, escape(&s)),
             None => main_part,
         };
         if self.paused.clone().unwrap() {
@@ -124,6 +151,30 @@ impl GenericReminder for reminder::ActiveModel {
     fn is_paused(&self) -> bool {
         self.paused.clone().unwrap()
     }
+
+    fn upcoming_times(
+        &self,
+        _user_timezone: Tz,
+        calendar: Calendar,
+        count: usize,
+    ) -> Vec<NaiveDateTime> {
+        let time = self.get_time();
+        let mut times = vec![time];
+        if let Some(mut pattern) = self
+            .pattern
+            .clone()
+            .unwrap()
+            .and_then(|pattern| from_str::<Pattern>(&pattern).ok())
+        {
+            // the scheduled time is counted off when it's sent
+            if pattern.count_occurrence() {
+                times.extend(
+                    pattern.occurrences(time, calendar).take(count - 1),
+                );
+            }
+        }
+        times
+    }
 }
 
 impl GenericReminder for cron_reminder::ActiveModel {
@@ -178,6 +229,24 @@ impl GenericReminder for cron_reminder::ActiveModel {
     fn is_paused(&self) -> bool {
         self.paused.clone().unwrap()
     }
+
+    fn upcoming_times(
+        &self,
+        user_timezone: Tz,
+        _calendar: Calendar,
+        count: usize,
+    ) -> Vec<NaiveDateTime> {
+        let cron_expr = self.cron_expr.clone().unwrap();
+        let mut times = vec![self.get_time()];
+        while times.len() < count {
+            let last = user_timezone.from_utc_datetime(times.last().unwrap());
+            match parse_cron(&cron_expr, &last) {
+                Ok(time) => times.push(time.naive_utc()),
+                Err(_) => break,
+            }
+        }
+        times
+    }
 }
 
 impl Ord for dyn GenericReminder {
@@ -17,7 +17,7 @@ use crate::{
         EditMode, ReminderUpdate, TgCallbackController, TgMessageController,
     },
     tg::TgResponse,
-    tz::{self, get_timezone_name_of_location},
+    tz,
 };
 
 #[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
@@ -55,6 +55,8 @@ pub(crate) enum Command {
     Pause,
     #[command(description = "set a new reminder")]
     Set(String),
+    #[command(description = "show the next times of a reminder")]
+    Preview(String),
     #[command(description = "select a timezone")]
     SetTimezone,
     #[command(description = "show your timezone")]
@@ -100,6 +102,10 @@ pub(crate) fn get_handler(
                         .branch(case![Command::Cancel].endpoint(cancel_handler))
                         .branch(case![Command::Pause].endpoint(pause_handler))
                         .branch(case![Command::Set(text)].endpoint(set_handler))
+                        .branch(
+                            case![Command::Preview(text)]
+                                .endpoint(preview_handler),
+                        )
                         .endpoint(incorrect_request_handler),
                 )
                 .endpoint(set_timezone_handler),
@@ -172,6 +178,24 @@ pub(crate) fn get_handler(
                     })
                     .endpoint(select_language_handler),
                 )
+                .branch(
+                    dptree::filter(|cb_data: String| {
+                        cb_data.starts_with("setholidays::")
+                    })
+                    .endpoint(select_holidays_handler),
+                )
+                .branch(
+                    dptree::filter(|cb_data: String| {
+                        cb_data.starts_with("setcatchup::")
+                    })
+                    .endpoint(select_catch_up_handler),
+                )
+                .branch(
+                    dptree::filter(|cb_data: String| {
+                        cb_data.starts_with("setdst::")
+                    })
+                    .endpoint(select_dst_handler),
+                )
                 .branch(
                     dptree::filter(|cb_data: String| {
                         cb_data.starts_with("settings::")
@@ -266,6 +290,16 @@ async fn set_handler(
         .map_err(From::from)
 }
 
+async fn preview_handler(
+    ctl: TgMessageController,
+    reminder_text: String,
+    user_tz: Tz,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ctl.preview(&reminder_text, user_tz)
+        .await
+        .map_err(From::from)
+}
+
 async fn set_edited_handler(
     ctl: TgMessageController,
     reminder_text: String,
@@ -304,7 +338,7 @@ async fn location_handler(
     ctl: TgMessageController,
     loc: Location,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    ctl.set_timezone(get_timezone_name_of_location(loc.longitude, loc.latitude))
+    ctl.set_location(loc.latitude, loc.longitude)
         .await
         .map_err(From::from)
 }
@@ -398,6 +432,42 @@ async fn select_language_handler(
     }
 }
 
+async fn select_holidays_handler(
+    ctl: TgCallbackController,
+    cb_data: String,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(country_code) = cb_data.strip_prefix("setholidays::country::") {
+        ctl.set_holidays(country_code).await.map_err(From::from)
+    } else {
+        ctl.msg_ctl.reply(TgResponse::IncorrectRequest).await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
+    }
+}
+
+async fn select_catch_up_handler(
+    ctl: TgCallbackController,
+    cb_data: String,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(policy_code) = cb_data.strip_prefix("setcatchup::policy::") {
+        ctl.set_catch_up(policy_code).await.map_err(From::from)
+    } else {
+        ctl.msg_ctl.reply(TgResponse::IncorrectRequest).await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
+    }
+}
+
+async fn select_dst_handler(
+    ctl: TgCallbackController,
+    cb_data: String,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(setting) = cb_data.strip_prefix("setdst::") {
+        ctl.set_dst(setting).await.map_err(From::from)
+    } else {
+        ctl.msg_ctl.reply(TgResponse::IncorrectRequest).await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
+    }
+}
+
 async fn settings_menu_handler(
     ctl: TgCallbackController,
     cb_data: String,
@@ -405,6 +475,15 @@ async fn settings_menu_handler(
     if cb_data == "settings::change_lang" {
         ctl.msg_ctl.choose_language().await?;
         ctl.acknowledge_callback().await.map_err(From::from)
+    } else if cb_data == "settings::change_holidays" {
+        ctl.msg_ctl.choose_holidays().await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
+    } else if cb_data == "settings::change_catch_up" {
+        ctl.msg_ctl.choose_catch_up().await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
+    } else if cb_data == "settings::change_dst" {
+        ctl.msg_ctl.choose_dst().await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
     } else {
         ctl.msg_ctl.reply(TgResponse::IncorrectRequest).await?;
         ctl.acknowledge_callback().await.map_err(From::from)
@@ -413,6 +492,7 @@ async fn settings_menu_handler(
 
 async fn callback_handler(
     ctl: TgCallbackController,
+    cb_query: CallbackQuery,
     cb_data: String,
     user_tz: Tz,
     dialogue: MyDialogue,
@@ -432,6 +512,25 @@ async fn callback_handler(
         ctl.acknowledge_callback().await.map_err(From::from)
     } else if let Some(lang_code) = cb_data.strip_prefix("setlang::lang::") {
         ctl.set_language(lang_code).await.map_err(From::from)
+    } else if cb_data == "settings::change_holidays" {
+        ctl.msg_ctl.choose_holidays().await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
+    } else if let Some(country_code) =
+        cb_data.strip_prefix("setholidays::country::")
+    {
+        ctl.set_holidays(country_code).await.map_err(From::from)
+    } else if cb_data == "settings::change_catch_up" {
+        ctl.msg_ctl.choose_catch_up().await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
+    } else if let Some(policy_code) =
+        cb_data.strip_prefix("setcatchup::policy::")
+    {
+        ctl.set_catch_up(policy_code).await.map_err(From::from)
+    } else if cb_data == "settings::change_dst" {
+        ctl.msg_ctl.choose_dst().await?;
+        ctl.acknowledge_callback().await.map_err(From::from)
+    } else if let Some(setting) = cb_data.strip_prefix("setdst::") {
+        ctl.set_dst(setting).await.map_err(From::from)
     } else if let Some(page_num) = cb_data
         .strip_prefix("delrem::page::")
         .and_then(|x| x.parse::<usize>().ok())
@@ -527,6 +626,22 @@ async fn callback_handler(
             })
             .await
             .map_err(From::from)
+    } else if cb_data == "preview::upcoming" {
+        ctl.show_upcoming_times(user_tz).await.map_err(From::from)
+    } else if cb_data == "didyoumean::apply" {
+        match cb_query
+            .regular_message()
+            .and_then(Message::reply_to_message)
+        {
+            Some(original) => ctl
+                .apply_correction(original, user_tz)
+                .await
+                .map_err(From::from),
+            None => {
+                ctl.msg_ctl.reply(TgResponse::IncorrectRequest).await?;
+                ctl.acknowledge_callback().await.map_err(From::from)
+            }
+        }
     } else {
         ctl.msg_ctl.reply(TgResponse::IncorrectRequest).await?;
         ctl.acknowledge_callback().await.map_err(From::from)
@@ -0,0 +1,17 @@
+#[cfg(not(test))]
+use crate::db::Database;
+#[cfg(test)]
+use crate::db::MockDatabase as Database;
+use remindee_pattern::Country;
+use teloxide::types::UserId;
+
+pub async fn get_user_holidays(
+    db: &Database,
+    user_id: UserId,
+) -> Option<Country> {
+    db.get_user_holidays_country(user_id.0 as i64)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|code| Country::from_code(&code))
+}
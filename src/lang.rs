@@ -28,6 +28,14 @@ impl Language {
         }
     }
 
+    pub(crate) const fn grammar_language(self) -> remindee_parser::Language {
+        match self {
+            Language::English => remindee_parser::Language::English,
+            Language::Dutch => remindee_parser::Language::Dutch,
+            Language::Russian => remindee_parser::Language::Russian,
+        }
+    }
+
     pub(crate) fn from_code(code: &str) -> Option<Self> {
         match code {
             "en" => Some(Self::English),
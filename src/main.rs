@@ -7,19 +7,20 @@ extern crate rust_i18n;
 i18n!("locales", fallback = "en");
 
 mod bot;
+mod catch_up;
 mod cli;
 mod controller;
-mod date;
 mod db;
 mod entity;
 mod err;
 mod format;
 mod generic_reminder;
 mod handlers;
+mod holidays;
 mod lang;
 mod migration;
 mod parsers;
-mod serializers;
+mod sun;
 mod tg;
 mod tz;
 
@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserLocation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserLocation::UserId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(UserLocation::Latitude)
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserLocation::Longitude)
+                            .double()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserLocation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum UserLocation {
+    Table,
+    UserId,
+    Latitude,
+    Longitude,
+}
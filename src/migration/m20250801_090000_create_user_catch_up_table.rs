@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserCatchUp::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserCatchUp::UserId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(UserCatchUp::Policy).text().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserCatchUp::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum UserCatchUp {
+    Table,
+    UserId,
+    Policy,
+}
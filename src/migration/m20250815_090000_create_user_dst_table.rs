@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserDst::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserDst::UserId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(UserDst::Gap).text().not_null())
+                    .col(ColumnDef::new(UserDst::Overlap).text().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserDst::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum UserDst {
+    Table,
+    UserId,
+    Gap,
+    Overlap,
+}
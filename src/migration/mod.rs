@@ -17,6 +17,10 @@ mod m20241114_105214_create_reply_id_columns;
 mod m20241114_105217_add_reply_id_indexes;
 mod m20241217_154950_remove_edit_columns;
 mod m20250618_171311_create_user_language_table;
+mod m20250704_120000_create_user_holidays_table;
+mod m20250712_090000_create_user_location_table;
+mod m20250801_090000_create_user_catch_up_table;
+mod m20250815_090000_create_user_dst_table;
 
 pub struct Migrator;
 
@@ -45,6 +49,10 @@ impl MigratorTrait for Migrator {
             Box::new(m20241114_105217_add_reply_id_indexes::Migration),
             Box::new(m20241217_154950_remove_edit_columns::Migration),
             Box::new(m20250618_171311_create_user_language_table::Migration),
+            Box::new(m20250704_120000_create_user_holidays_table::Migration),
+            Box::new(m20250712_090000_create_user_location_table::Migration),
+            Box::new(m20250801_090000_create_user_catch_up_table::Migration),
+            Box::new(m20250815_090000_create_user_dst_table::Migration),
         ]
     }
 }
@@ -1,32 +1,51 @@
-use crate::serializers::Pattern;
-
 use crate::entity::{cron_reminder, reminder};
+use crate::lang::Language;
 use chrono::prelude::*;
 use chrono::Utc;
 use chrono_tz::Tz;
 use cron_parser::parse as parse_cron;
+use remindee_parser::ParseError;
+pub(crate) use remindee_pattern::now_time;
+use remindee_pattern::{Calendar, Pattern};
 use sea_orm::ActiveValue::{NotSet, Set};
 use serde_json::to_string;
 
-#[cfg(not(test))]
-pub(crate) fn now_time() -> NaiveDateTime {
-    Utc::now().naive_utc()
+/// The user's settings that a reminder is parsed with
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct UserSettings {
+    pub(crate) timezone: Tz,
+    pub(crate) language: Language,
+    pub(crate) calendar: Calendar,
 }
 
+/// Parse a reminder, returning `Ok(None)` if it's well-formed
+/// but never occurs, and the parsing error otherwise
 pub(crate) async fn parse_reminder(
     s: &str,
     chat_id: i64,
     user_id: u64,
     msg_id: i32,
-    user_timezone: Tz,
-) -> Option<reminder::ActiveModel> {
-    let rem = remindee_parser::parse_reminder(s)?;
+    settings: &UserSettings,
+) -> Result<Option<reminder::ActiveModel>, ParseError> {
+    let rem = remindee_parser::parse_reminder_in(
+        s,
+        settings.language.grammar_language(),
+    )?;
     let description = rem.description.map(|x| x.0).unwrap_or("".to_owned());
-    let mut pattern =
-        Pattern::from_with_tz(rem.pattern?, user_timezone).ok()?;
-    let time = pattern.next(now_time())?;
+    let Some(mut pattern) = rem.pattern.and_then(|pattern| {
+        Pattern::from_with_tz(
+            pattern,
+            settings.timezone,
+            settings.calendar.location,
+        )
+    }) else {
+        return Ok(None);
+    };
+    let Some(time) = pattern.next(now_time(), settings.calendar) else {
+        return Ok(None);
+    };
     // Convert to UTC
-    Some(reminder::ActiveModel {
+    Ok(Some(reminder::ActiveModel {
         id: NotSet,
         chat_id: Set(chat_id),
         user_id: Set(Some(user_id as i64)),
@@ -36,7 +55,25 @@ pub(crate) async fn parse_reminder(
         pattern: Set(to_string(&pattern).ok()),
         msg_id: Set(Some(msg_id)),
         reply_id: Set(None), // set after replying
-    })
+    }))
+}
+
+/// Whether the reminder has times relative to the sun,
+/// so that it can't be set without a location
+pub(crate) fn uses_sun_times(s: &str, user_language: Language) -> bool {
+    remindee_parser::parse_reminder_in(s, user_language.grammar_language())
+        .ok()
+        .and_then(|rem| rem.pattern)
+        .is_some_and(|pattern| remindee_pattern::uses_sun_times(&pattern))
+}
+
+/// The text with misspelled keywords corrected
+/// if that makes more of it a reminder pattern
+pub(crate) fn suggest_correction(
+    s: &str,
+    user_language: Language,
+) -> Option<String> {
+    remindee_parser::suggest_correction(s, user_language.grammar_language())
 }
 
 pub(crate) async fn parse_cron_reminder(
@@ -71,20 +108,16 @@ pub(crate) async fn parse_cron_reminder(
     }
 }
 
-#[cfg(test)]
-pub(crate) fn now_time() -> NaiveDateTime {
-    DateTime::from_timestamp(*test::TEST_TIMESTAMP.read().unwrap(), 0)
-        .unwrap()
-        .naive_utc()
-}
-
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
+    use remindee_parser::{Expected, ParseErrorKind};
+    use remindee_pattern::Location;
+    pub(crate) use remindee_pattern::TEST_TIMESTAMP;
     use serial_test::serial;
     use test_case::test_case;
     extern crate strfmt;
-    use std::{collections::HashMap, sync::RwLock};
+    use std::collections::HashMap;
     use strfmt::strfmt;
 
     lazy_static! {
@@ -94,9 +127,26 @@ pub(crate) mod test {
             TEST_TZ.with_ymd_and_hms(2007, 2, 2, 12, 30, 30).unwrap();
     }
 
-    pub(crate) static TEST_TIMESTAMP: RwLock<i64> = RwLock::new(0);
+    pub(crate) const TEST_LOCATION: Location = Location {
+        latitude: 55.75,
+        longitude: 37.62,
+    };
     const TEST_DESCRIPTION: &str = "reminder description";
 
+    fn test_settings(
+        language: Language,
+        location: Option<Location>,
+    ) -> UserSettings {
+        UserSettings {
+            timezone: *TEST_TZ,
+            language,
+            calendar: Calendar {
+                location,
+                ..Calendar::default()
+            },
+        }
+    }
+
     #[derive(Debug, PartialEq)]
     struct Time(i32, u32, u32, u32, u32, u32);
 
@@ -112,6 +162,53 @@ pub(crate) mod test {
     #[test_case("{day} {hour} {desc}", Time(2007, 2, 1, 13, 0, 0) => Some(Time(2007, 3, 1, 13, 0, 0)) ; "day before" )]
     #[test_case("02.01 13:00 {desc}", Time(2007, 1, 2, 13, 0, 0) => Some(Time(2008, 1, 2, 13, 0, 0)) ; "month before" )]
     #[test_case("{hour}:{minute}{desc}", Time(2007, 2, 2, 12, 30, 0) => None ; "non-parsable" )]
+    #[test_case("5pm {desc}", Time(2007, 2, 2, 17, 0, 0) => Some(Time(2007, 2, 2, 17, 0, 0)) ; "pm" )]
+    #[test_case("at 7:30 am {desc}", Time(2007, 2, 3, 7, 30, 0) => Some(Time(2007, 2, 3, 7, 30, 0)) ; "am with minutes" )]
+    #[test_case("12am {desc}", Time(2007, 2, 3, 0, 0, 0) => Some(Time(2007, 2, 3, 0, 0, 0)) ; "12am" )]
+    #[test_case("12:45 p.m. {desc}", Time(2007, 2, 2, 12, 45, 0) => Some(Time(2007, 2, 2, 12, 45, 0)) ; "12pm" )]
+    #[test_case("noon {desc}", Time(2007, 2, 3, 12, 0, 0) => Some(Time(2007, 2, 3, 12, 0, 0)) ; "noon" )]
+    #[test_case("at midnight {desc}", Time(2007, 2, 3, 0, 0, 0) => Some(Time(2007, 2, 3, 0, 0, 0)) ; "midnight" )]
+    #[test_case("15 may 10:00 {desc}", Time(2007, 5, 15, 10, 0, 0) => Some(Time(2007, 5, 15, 10, 0, 0)) ; "day month name" )]
+    #[test_case("dec 24 at 18:00 {desc}", Time(2007, 12, 24, 18, 0, 0) => Some(Time(2007, 12, 24, 18, 0, 0)) ; "month name day" )]
+    #[test_case("Jan 12, 2027 9:00 {desc}", Time(2027, 1, 12, 9, 0, 0) => Some(Time(2027, 1, 12, 9, 0, 0)) ; "month name day year" )]
+    #[test_case("5 March 2008 8:00 {desc}", Time(2008, 3, 5, 8, 0, 0) => Some(Time(2008, 3, 5, 8, 0, 0)) ; "day month name year" )]
+    #[test_case("1 feb. 10:00 {desc}", Time(2008, 2, 1, 10, 0, 0) => Some(Time(2008, 2, 1, 10, 0, 0)) ; "month name before" )]
+    #[test_case("29 feb 10:00 {desc}", Time(2007, 2, 28, 10, 0, 0) => Some(Time(2007, 2, 28, 10, 0, 0)) ; "month name normalised day" )]
+    #[test_case("in 2 days at 10:00 {desc}", Time(2007, 2, 4, 10, 0, 0) => Some(Time(2007, 2, 4, 10, 0, 0)) ; "countdown with time" )]
+    #[test_case("in 1 week on fri 9:00 {desc}", Time(2007, 2, 9, 9, 0, 0) => Some(Time(2007, 2, 9, 9, 0, 0)) ; "countdown with weekday" )]
+    #[test_case("in 5 minutes {desc}", Time(2007, 2, 2, 12, 35, 30) => Some(Time(2007, 2, 2, 12, 35, 30)) ; "countdown with spaced unit" )]
+    #[test_case("in five minutes {desc}", Time(2007, 2, 2, 12, 35, 30) => Some(Time(2007, 2, 2, 12, 35, 30)) ; "spelled minutes" )]
+    #[test_case("in twenty-five minutes {desc}", Time(2007, 2, 2, 12, 55, 30) => Some(Time(2007, 2, 2, 12, 55, 30)) ; "spelled compound number" )]
+    #[test_case("in forty five seconds {desc}", Time(2007, 2, 2, 12, 31, 15) => Some(Time(2007, 2, 2, 12, 31, 15)) ; "spelled compound number with space" )]
+    #[test_case("in an hour {desc}", Time(2007, 2, 2, 13, 30, 30) => Some(Time(2007, 2, 2, 13, 30, 30)) ; "an hour" )]
+    #[test_case("in half an hour {desc}", Time(2007, 2, 2, 13, 0, 30) => Some(Time(2007, 2, 2, 13, 0, 30)) ; "half an hour" )]
+    #[test_case("in a quarter of an hour {desc}", Time(2007, 2, 2, 12, 45, 30) => Some(Time(2007, 2, 2, 12, 45, 30)) ; "quarter of an hour" )]
+    #[test_case("in two days at 10:00 {desc}", Time(2007, 2, 4, 10, 0, 0) => Some(Time(2007, 2, 4, 10, 0, 0)) ; "spelled delay" )]
+    #[test_case("in 1.5h {desc}", Time(2007, 2, 2, 14, 0, 30) => Some(Time(2007, 2, 2, 14, 0, 30)) ; "decimal hours" )]
+    #[test_case("in 0.25 days {desc}", Time(2007, 2, 2, 18, 30, 30) => Some(Time(2007, 2, 2, 18, 30, 30)) ; "decimal days" )]
+    #[test_case("in 1.5y {desc}", Time(2008, 8, 2, 12, 30, 30) => Some(Time(2008, 8, 2, 12, 30, 30)) ; "decimal years" )]
+    #[test_case("PT1H30M {desc}", Time(2007, 2, 2, 14, 0, 30) => Some(Time(2007, 2, 2, 14, 0, 30)) ; "iso duration" )]
+    #[test_case("in P1DT2H {desc}", Time(2007, 2, 3, 14, 30, 30) => Some(Time(2007, 2, 3, 14, 30, 30)) ; "iso duration with date" )]
+    #[test_case("PT1.5H {desc}", Time(2007, 2, 2, 14, 0, 30) => Some(Time(2007, 2, 2, 14, 0, 30)) ; "iso decimal duration" )]
+    #[test_case("2027-03-01T10:00 {desc}", Time(2027, 3, 1, 10, 0, 0) => Some(Time(2027, 3, 1, 10, 0, 0)) ; "iso datetime" )]
+    #[test_case("2027-03-01T10:00:15 {desc}", Time(2027, 3, 1, 10, 0, 15) => Some(Time(2027, 3, 1, 10, 0, 15)) ; "iso datetime with seconds" )]
+    #[test_case("2027-03-01 10:00 {desc}", Time(2027, 3, 1, 10, 0, 0) => Some(Time(2027, 3, 1, 10, 0, 0)) ; "iso date" )]
+    #[test_case("2027-03-01T10:00Z {desc}", Time(2027, 3, 1, 13, 0, 0) => Some(Time(2027, 3, 1, 13, 0, 0)) ; "iso datetime utc" )]
+    #[test_case("2027-03-01T10:00+02:00 {desc}", Time(2027, 3, 1, 11, 0, 0) => Some(Time(2027, 3, 1, 11, 0, 0)) ; "iso datetime offset" )]
+    #[test_case("2027-03-01T10:00+05:30 {desc}", Time(2027, 3, 1, 7, 30, 0) => Some(Time(2027, 3, 1, 7, 30, 0)) ; "iso datetime fractional offset" )]
+    #[test_case("10:00 UTC+5:45 {desc}", Time(2007, 2, 3, 7, 15, 0) => Some(Time(2007, 2, 3, 7, 15, 0)) ; "fractional offset" )]
+    #[test_case("on mon 10:00, wed 15:30 {desc}", Time(2007, 2, 5, 10, 0, 0) => Some(Time(2007, 2, 5, 10, 0, 0)) ; "date-specific times" )]
+    #[test_case("22:00-02:00 every 30m {desc}", Time(2007, 2, 2, 22, 0, 0) => Some(Time(2007, 2, 2, 22, 0, 0)) ; "time range crossing midnight" )]
+    #[test_case("every 1d 9:00 x10 {desc}", Time(2007, 2, 3, 9, 0, 0) => Some(Time(2007, 2, 3, 9, 0, 0)) ; "count limit" )]
+    #[test_case("every 1d 9:00 ten times {desc}", Time(2007, 2, 3, 9, 0, 0) => Some(Time(2007, 2, 3, 9, 0, 0)) ; "spelled count limit" )]
+    #[test_case("every 1h for 3 days {desc}", Time(2007, 2, 2, 13, 0, 0) => Some(Time(2007, 2, 2, 13, 0, 0)) ; "duration limit" )]
+    #[test_case("{desc} every 1d at 9:00 for a week", Time(2007, 2, 3, 9, 0, 0) => Some(Time(2007, 2, 3, 9, 0, 0)) ; "duration limit after description" )]
+    #[test_case("every mon in dec 10:00 {desc}", Time(2007, 12, 3, 10, 0, 0) => Some(Time(2007, 12, 3, 10, 0, 0)) ; "month filter" )]
+    #[test_case("friday the 13th 10:00 {desc}", Time(2007, 4, 13, 10, 0, 0) => Some(Time(2007, 4, 13, 10, 0, 0)) ; "weekday on a day" )]
+    #[test_case("every 1d 8:00 in summer {desc}", Time(2007, 6, 1, 8, 0, 0) => Some(Time(2007, 6, 1, 8, 0, 0)) ; "season filter" )]
+    #[test_case("every 1d sunset {desc}", Time(2007, 2, 2, 17, 4, 0) => Some(Time(2007, 2, 2, 17, 4, 0)) ; "sunset" )]
+    #[test_case("every 1d 30m before sunset {desc}", Time(2007, 2, 2, 16, 34, 0) => Some(Time(2007, 2, 2, 16, 34, 0)) ; "before sunset" )]
+    #[test_case("sunrise+1h {desc}", Time(2007, 2, 3, 9, 20, 0) => Some(Time(2007, 2, 3, 9, 20, 0)) ; "after sunrise" )]
     #[tokio::test]
     #[serial]
     async fn test_parse_reminder(fmt_str: &str, time: Time) -> Option<Time> {
@@ -127,15 +224,22 @@ pub(crate) mod test {
             ("desc".to_owned(), TEST_DESCRIPTION.to_owned()),
         ]);
         *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
-        let result =
-            parse_reminder(&strfmt(fmt_str, &vars).unwrap(), 0, 0, 0, *TEST_TZ)
-                .await
-                .map(|reminder| {
-                    (
-                        TEST_TZ.from_utc_datetime(&reminder.time.unwrap()),
-                        reminder.desc.unwrap(),
-                    )
-                });
+        let result = parse_reminder(
+            &strfmt(fmt_str, &vars).unwrap(),
+            0,
+            0,
+            0,
+            &test_settings(Language::English, Some(TEST_LOCATION)),
+        )
+        .await
+        .ok()
+        .flatten()
+        .map(|reminder| {
+            (
+                TEST_TZ.from_utc_datetime(&reminder.time.unwrap()),
+                reminder.desc.unwrap(),
+            )
+        });
         match result {
             Some((time, desc)) => {
                 assert_eq!(desc, TEST_DESCRIPTION.to_owned());
@@ -151,4 +255,188 @@ pub(crate) mod test {
             None => None,
         }
     }
+
+    #[test_case("every 1d randomly between 19:00 and 21:00 ping", Language::English, (19, 21) ; "random time")]
+    #[test_case("three times randomly 14-16 ping", Language::English, (14, 16) ; "random times")]
+    #[test_case("каждый 1д случайно между 19:00 и 21:00 ping", Language::Russian, (19, 21) ; "ru random time")]
+    #[test_case("elke 1d willekeurig tussen 19:00 en 21:00 ping", Language::Dutch, (19, 21) ; "nl random time")]
+    #[tokio::test]
+    #[serial]
+    async fn test_parse_random_reminder(
+        s: &str,
+        language: Language,
+        (from, until): (u32, u32),
+    ) {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let reminder =
+            parse_reminder(s, 0, 0, 0, &test_settings(language, None))
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(reminder.desc.unwrap(), "ping");
+        let time = TEST_TZ.from_utc_datetime(&reminder.time.unwrap());
+        assert_eq!(time.date_naive(), TEST_TIME.date_naive());
+        assert!((from..until).contains(&time.hour()));
+    }
+
+    #[test_case("в 17:00 позвонить в магазин", Language::Russian => Some((Time(2007, 2, 2, 17, 0, 0), "позвонить в магазин".to_owned())) ; "ru at")]
+    #[test_case("завтра в 9:00 зарядка", Language::Russian => Some((Time(2007, 2, 3, 9, 0, 0), "зарядка".to_owned())) ; "ru tomorrow")]
+    #[test_case("через 1ч30мин чай", Language::Russian => Some((Time(2007, 2, 2, 14, 0, 30), "чай".to_owned())) ; "ru countdown")]
+    #[test_case("каждый пн 10:00 планёрка", Language::Russian => Some((Time(2007, 2, 5, 10, 0, 0), "планёрка".to_owned())) ; "ru every weekday")]
+    #[test_case("по пн-пт в 10:00 зарядка", Language::Russian => Some((Time(2007, 2, 5, 10, 0, 0), "зарядка".to_owned())) ; "ru weekdays range")]
+    #[test_case("Во вторник в 8 бассейн", Language::Russian => Some((Time(2007, 2, 6, 8, 0, 0), "бассейн".to_owned())) ; "ru on weekday")]
+    #[test_case("every пн at 10:00 планёрка", Language::Russian => Some((Time(2007, 2, 5, 10, 0, 0), "планёрка".to_owned())) ; "ru mixed with en")]
+    #[test_case("om 17:00 mama bellen", Language::Dutch => Some((Time(2007, 2, 2, 17, 0, 0), "mama bellen".to_owned())) ; "nl at")]
+    #[test_case("morgen om 9:00 sporten", Language::Dutch => Some((Time(2007, 2, 3, 9, 0, 0), "sporten".to_owned())) ; "nl tomorrow")]
+    #[test_case("over 2uur thee", Language::Dutch => Some((Time(2007, 2, 2, 14, 30, 30), "thee".to_owned())) ; "nl countdown")]
+    #[test_case("elke ma 10:00 overleg", Language::Dutch => Some((Time(2007, 2, 5, 10, 0, 0), "overleg".to_owned())) ; "nl every weekday")]
+    #[test_case("op di-do 8:00 zwemmen", Language::Dutch => Some((Time(2007, 2, 6, 8, 0, 0), "zwemmen".to_owned())) ; "nl weekdays range")]
+    #[test_case("every maandag om 9:00 overleg", Language::Dutch => Some((Time(2007, 2, 5, 9, 0, 0), "overleg".to_owned())) ; "nl mixed with en")]
+    #[test_case("every monday at 9:00 meeting", Language::Dutch => Some((Time(2007, 2, 5, 9, 0, 0), "meeting".to_owned())) ; "nl plain en")]
+    #[test_case("5 марта в 10:00 поздравить", Language::Russian => Some((Time(2007, 3, 5, 10, 0, 0), "поздравить".to_owned())) ; "ru month name")]
+    #[test_case("3 mei om 9:00 verjaardag", Language::Dutch => Some((Time(2007, 5, 3, 9, 0, 0), "verjaardag".to_owned())) ; "nl month name")]
+    #[test_case("через 2 дня в 10:00 отчёт", Language::Russian => Some((Time(2007, 2, 4, 10, 0, 0), "отчёт".to_owned())) ; "ru countdown with time")]
+    #[test_case("через пять минут чай", Language::Russian => Some((Time(2007, 2, 2, 12, 35, 30), "чай".to_owned())) ; "ru spelled number")]
+    #[test_case("через двадцать одну минуту чай", Language::Russian => Some((Time(2007, 2, 2, 12, 51, 30), "чай".to_owned())) ; "ru spelled compound number")]
+    #[test_case("через полчаса чай", Language::Russian => Some((Time(2007, 2, 2, 13, 0, 30), "чай".to_owned())) ; "ru half an hour")]
+    #[test_case("over vijfentwintig minuten thee", Language::Dutch => Some((Time(2007, 2, 2, 12, 55, 30), "thee".to_owned())) ; "nl spelled compound number")]
+    #[test_case("over een kwartier thee", Language::Dutch => Some((Time(2007, 2, 2, 12, 45, 30), "thee".to_owned())) ; "nl quarter hour")]
+    #[test_case("over een uur thee", Language::Dutch => Some((Time(2007, 2, 2, 13, 30, 30), "thee".to_owned())) ; "nl an hour")]
+    #[test_case("по будням в 10:00 планёрка", Language::Russian => Some((Time(2007, 2, 5, 10, 0, 0), "планёрка".to_owned())) ; "ru workdays")]
+    #[test_case("в выходные в 11:00 бранч", Language::Russian => Some((Time(2007, 2, 3, 11, 0, 0), "бранч".to_owned())) ; "ru weekends")]
+    #[test_case("elke werkdag om 9:00 overleg", Language::Dutch => Some((Time(2007, 2, 5, 9, 0, 0), "overleg".to_owned())) ; "nl workdays")]
+    #[test_case("по понедельникам в декабре в 10:00 планёрка", Language::Russian => Some((Time(2007, 12, 3, 10, 0, 0), "планёрка".to_owned())) ; "ru month filter")]
+    #[test_case("каждый 1д летом в 8:00 полив", Language::Russian => Some((Time(2007, 6, 1, 8, 0, 0), "полив".to_owned())) ; "ru season filter")]
+    #[test_case("каждый 1д на закате полить цветы", Language::Russian => Some((Time(2007, 2, 2, 17, 4, 0), "полить цветы".to_owned())) ; "ru sunset")]
+    #[test_case("elke 1d 30 minuten voor zonsondergang wandelen", Language::Dutch => Some((Time(2007, 2, 2, 16, 34, 0), "wandelen".to_owned())) ; "nl before sunset")]
+    #[test_case("elke 1d in de zomer om 8:00 water geven", Language::Dutch => Some((Time(2007, 6, 1, 8, 0, 0), "water geven".to_owned())) ; "nl season filter")]
+    #[test_case("every 1d 18:00 if my car is free", Language::English => Some((Time(2007, 2, 2, 18, 0, 0), "if my car is free".to_owned())) ; "word after if is not a weekday")]
+    #[test_case("every mon 9:00, then call mom", Language::English => Some((Time(2007, 2, 5, 9, 0, 0), "then call mom".to_owned())) ; "comma before description")]
+    #[test_case("every 1d 18:00, sat is free", Language::English => Some((Time(2007, 2, 2, 18, 0, 0), "sat is free".to_owned())) ; "weekday without time after comma")]
+    #[test_case("other day I saw a movie", Language::English => None ; "other without every")]
+    #[test_case("a year ago we met at 18:00", Language::English => Some((Time(2007, 2, 2, 18, 0, 0), "a year ago we met".to_owned())) ; "bare article is not a countdown")]
+    #[test_case("meeting tomorrow at 10:00 for an hour", Language::English => Some((Time(2007, 2, 3, 10, 0, 0), "meeting for an hour".to_owned())) ; "duration after one-off time")]
+    #[test_case("tomorrow at 10:00 x3 pills", Language::English => Some((Time(2007, 2, 3, 10, 0, 0), "x3 pills".to_owned())) ; "count after one-off time")]
+    #[test_case("18:00 milk/eggs", Language::English => Some((Time(2007, 2, 2, 18, 0, 0), "milk/eggs".to_owned())) ; "slash word is not a timezone")]
+    #[test_case("10:00 PT session", Language::English => Some((Time(2007, 2, 3, 10, 0, 0), "PT session".to_owned())) ; "short word is not a timezone")]
+    #[test_case("в 17:00 позвонить", Language::English => Some((Time(2007, 2, 2, 17, 0, 0), "в позвонить".to_owned())) ; "ru keywords ignored for en")]
+    #[test_case("через 1ч чай", Language::English => None ; "ru countdown ignored for en")]
+    #[test_case("in 9999999y test", Language::English => None ; "countdown out of range")]
+    #[test_case("every 99999999y test", Language::English => None ; "recurrence out of range")]
+    #[tokio::test]
+    #[serial]
+    async fn test_parse_localized_reminder(
+        s: &str,
+        language: Language,
+    ) -> Option<(Time, String)> {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        parse_reminder(
+            s,
+            0,
+            0,
+            0,
+            &test_settings(language, Some(TEST_LOCATION)),
+        )
+        .await
+        .ok()
+        .flatten()
+        .map(|reminder| {
+            let time = TEST_TZ.from_utc_datetime(&reminder.time.unwrap());
+            (
+                Time(
+                    time.year(),
+                    time.month(),
+                    time.day(),
+                    time.hour(),
+                    time.minute(),
+                    time.second(),
+                ),
+                reminder.desc.unwrap(),
+            )
+        })
+    }
+
+    #[test_case("call mom tomorrow at 10:00" => Some((Time(2007, 2, 3, 10, 0, 0), "call mom".to_owned())) ; "trailing recurrence")]
+    #[test_case("call mom at 17:00" => Some((Time(2007, 2, 2, 17, 0, 0), "call mom".to_owned())) ; "trailing time")]
+    #[test_case("pay rent 1.03 10:00" => Some((Time(2007, 3, 1, 10, 0, 0), "pay rent".to_owned())) ; "trailing date")]
+    #[test_case("standup every monday at 9:00" => Some((Time(2007, 2, 5, 9, 0, 0), "standup".to_owned())) ; "trailing weekdays")]
+    #[test_case("take a break in 15m" => Some((Time(2007, 2, 2, 12, 45, 30), "take a break".to_owned())) ; "trailing countdown")]
+    #[test_case("call mom tomorrow at 10:00 about the bill" => Some((Time(2007, 2, 3, 10, 0, 0), "call mom about the bill".to_owned())) ; "inner pattern")]
+    #[test_case("read chapter 5 at 10:00" => Some((Time(2007, 2, 3, 10, 0, 0), "read chapter 5".to_owned())) ; "description ends with number")]
+    #[test_case("flight 2 14:00" => Some((Time(2007, 2, 2, 14, 0, 0), "flight 2".to_owned())) ; "number before time")]
+    #[test_case("10:00 meet at 11" => Some((Time(2007, 2, 3, 10, 0, 0), "meet at 11".to_owned())) ; "leading pattern preferred")]
+    #[test_case("in 2d 5 pages" => Some((Time(2007, 2, 4, 12, 30, 30), "5 pages".to_owned())) ; "countdown before number")]
+    #[test_case("one more thing at 10:00" => Some((Time(2007, 2, 3, 10, 0, 0), "one more thing".to_owned())) ; "number word in description")]
+    #[test_case("read chapter 5" => None ; "bare trailing number")]
+    #[test_case("buy 2 tickets" => None ; "bare inner number")]
+    #[tokio::test]
+    #[serial]
+    async fn test_parse_description_first(s: &str) -> Option<(Time, String)> {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        parse_reminder(s, 0, 0, 0, &test_settings(Language::English, None))
+            .await
+            .ok()
+            .flatten()
+            .map(|reminder| {
+                let time = TEST_TZ.from_utc_datetime(&reminder.time.unwrap());
+                (
+                    Time(
+                        time.year(),
+                        time.month(),
+                        time.day(),
+                        time.hour(),
+                        time.minute(),
+                        time.second(),
+                    ),
+                    reminder.desc.unwrap(),
+                )
+            })
+    }
+
+    #[test_case("evry mon 10:00 standup", Language::English => Some("every mon 10:00 standup".to_owned()) ; "missing letter")]
+    #[test_case("on wendesday 10:00 standup", Language::English => Some("on wednesday 10:00 standup".to_owned()) ; "swapped letters")]
+    #[test_case("every 2 hourz drink", Language::English => Some("every 2 hours drink".to_owned()) ; "failing reminder")]
+    #[test_case("Tomorow at 9 call", Language::English => Some("Tomorrow at 9 call".to_owned()) ; "capitalized")]
+    #[test_case("каждый понеделник в 10:00 планёрка", Language::Russian => Some("каждый понедельник в 10:00 планёрка".to_owned()) ; "localized")]
+    #[test_case("call mom at 17:00", Language::English => None ; "description kept")]
+    #[test_case("every 1d 9:00 standup", Language::English => None ; "no typos")]
+    fn test_suggest_correction(s: &str, language: Language) -> Option<String> {
+        suggest_correction(s, language)
+    }
+
+    #[test_case("31.02 10:00 {desc}", Language::English => (ParseErrorKind::InvalidDate, "31.02".to_owned()) ; "invalid date")]
+    #[test_case("29.02.2007 10:00 {desc}", Language::English => (ParseErrorKind::InvalidDate, "29.02.2007".to_owned()) ; "invalid leap date")]
+    #[test_case("every 0h {desc}", Language::English => (ParseErrorKind::ZeroInterval, "0h".to_owned()) ; "zero interval")]
+    #[test_case("- 9-18/0m {desc}", Language::English => (ParseErrorKind::ZeroInterval, "0m".to_owned()) ; "zero time divisor")]
+    #[test_case("tomorrow at 25:00 {desc}", Language::English => (ParseErrorKind::Syntax { expected: vec![Expected::Time] }, "".to_owned()) ; "bad time")]
+    #[test_case("{desc}", Language::English => (ParseErrorKind::Syntax { expected: vec![Expected::Date, Expected::Time, Expected::Interval] }, "".to_owned()) ; "no pattern")]
+    #[test_case("{desc} 31.02 10:00", Language::English => (ParseErrorKind::InvalidDate, "31.02".to_owned()) ; "trailing invalid date")]
+    #[test_case("every 1d 25:00 {desc}", Language::English => (ParseErrorKind::Syntax { expected: vec![Expected::Time] }, "".to_owned()) ; "bad time after interval")]
+    #[test_case("every 1y 29.02 10:00 {desc}", Language::English => (ParseErrorKind::Syntax { expected: vec![Expected::Time] }, "".to_owned()) ; "date after interval")]
+    #[test_case("2025-13-01 10:00 {desc}", Language::English => (ParseErrorKind::Syntax { expected: vec![Expected::Date, Expected::Weekday, Expected::Interval] }, "".to_owned()) ; "bad iso date")]
+    #[test_case("every day 9:00 {desc}", Language::English => (ParseErrorKind::Syntax { expected: vec![Expected::Weekday, Expected::Interval] }, "".to_owned()) ; "bad divisor")]
+    #[test_case("каждый день в 9:00 {desc}", Language::Russian => (ParseErrorKind::Syntax { expected: vec![Expected::Weekday, Expected::Interval] }, "".to_owned()) ; "localized bad divisor")]
+    #[test_case("elke dag 9:00 {desc}", Language::Dutch => (ParseErrorKind::Syntax { expected: vec![Expected::Weekday, Expected::Interval] }, "".to_owned()) ; "dutch bad divisor")]
+    #[test_case("evry mon 10:00 {desc}", Language::English => (ParseErrorKind::Syntax { expected: vec![Expected::Date, Expected::Time, Expected::Interval] }, "".to_owned()) ; "misspelled keyword")]
+    #[test_case("in 1.5mo {desc}", Language::English => (ParseErrorKind::FractionNotAllowed, "1.5".to_owned()) ; "decimal months")]
+    #[test_case("every 1.5d 10:00 {desc}", Language::English => (ParseErrorKind::FractionNotAllowed, "1.5".to_owned()) ; "decimal date interval")]
+    #[test_case("every 1d 9:00 x0 {desc}", Language::English => (ParseErrorKind::ZeroCount, "0".to_owned()) ; "zero count limit")]
+    #[test_case("every 1d 9:00 0 times {desc}", Language::English => (ParseErrorKind::ZeroCount, "0".to_owned()) ; "zero times limit")]
+    #[test_case("randomly 0 times 14-16 {desc}", Language::English => (ParseErrorKind::ZeroCount, "0".to_owned()) ; "zero random times")]
+    #[test_case("через 0мин {desc}", Language::Russian => (ParseErrorKind::ZeroInterval, "0мин".to_owned()) ; "localized span")]
+    #[tokio::test]
+    #[serial]
+    async fn test_parse_reminder_error(
+        fmt_str: &str,
+        language: Language,
+    ) -> (ParseErrorKind, String) {
+        *TEST_TIMESTAMP.write().unwrap() = TEST_TIME.timestamp();
+        let vars =
+            HashMap::from([("desc".to_owned(), TEST_DESCRIPTION.to_owned())]);
+        let s = strfmt(fmt_str, &vars).unwrap();
+        let err = parse_reminder(&s, 0, 0, 0, &test_settings(language, None))
+            .await
+            .unwrap_err();
+        (err.kind, s[err.span].to_owned())
+    }
 }
@@ -0,0 +1,20 @@
+#[cfg(not(test))]
+use crate::db::Database;
+#[cfg(test)]
+use crate::db::MockDatabase as Database;
+use remindee_pattern::Location;
+use teloxide::types::UserId;
+
+pub(crate) async fn get_user_location(
+    db: &Database,
+    user_id: UserId,
+) -> Option<Location> {
+    db.get_user_location(user_id.0 as i64)
+        .await
+        .ok()
+        .flatten()
+        .map(|(latitude, longitude)| Location {
+            latitude,
+            longitude,
+        })
+}
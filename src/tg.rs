@@ -6,10 +6,12 @@ use teloxide::prelude::*;
 use teloxide::types::ParseMode::MarkdownV2;
 use teloxide::types::{
     ChatId, InlineKeyboardMarkup, LinkPreviewOptions, MessageId,
+    ReplyParameters,
 };
 use teloxide::utils::markdown::escape;
 use teloxide::RequestError;
 
+use remindee_parser::{Expected, ParseError, ParseErrorKind};
 use rust_i18n::t;
 
 pub(crate) enum TgResponse {
@@ -17,6 +19,7 @@ pub(crate) enum TgResponse {
     SuccessPeriodicInsert(String),
     FailedInsert,
     IncorrectRequest,
+    InvalidReminder(String, ParseError),
     QueryingError,
     RemindersList(String),
     SelectTimezone,
@@ -43,6 +46,20 @@ pub(crate) enum TgResponse {
     SelectLanguage,
     ChosenLanguage,
     FailedSetLanguage(String),
+    SelectHolidays,
+    ChosenHolidays(String),
+    ChosenNoHolidays,
+    FailedSetHolidays,
+    SelectCatchUp,
+    ChosenCatchUp(String),
+    ChosenDefaultCatchUp,
+    FailedSetCatchUp,
+    SelectDst,
+    ChosenDst(String, String),
+    FailedSetDst,
+    LocationRequired,
+    UpcomingTimes(String),
+    ReminderNotFound,
     Help,
 }
 
@@ -63,6 +80,9 @@ impl TgResponse {
             Self::IncorrectRequest => {
                 t!("IncorrectRequest", locale = lang).to_string()
             }
+            Self::InvalidReminder(text, err) => {
+                invalid_reminder_hint(text, err, lang)
+            }
             Self::QueryingError => {
                 t!("QueryingError", locale = lang).to_string()
             }
@@ -136,6 +156,47 @@ impl TgResponse {
                 t!("FailedSetLanguage", locale = lang, lang = lang_name)
                     .to_string()
             }
+            Self::SelectHolidays => {
+                t!("SelectHolidays", locale = lang).to_string()
+            }
+            Self::ChosenHolidays(country_name) => {
+                t!("ChosenHolidays", locale = lang, country = country_name)
+                    .to_string()
+            }
+            Self::ChosenNoHolidays => {
+                t!("ChosenNoHolidays", locale = lang).to_string()
+            }
+            Self::FailedSetHolidays => {
+                t!("FailedSetHolidays", locale = lang).to_string()
+            }
+            Self::SelectCatchUp => {
+                t!("SelectCatchUp", locale = lang).to_string()
+            }
+            Self::ChosenCatchUp(policy_name) => {
+                t!("ChosenCatchUp", locale = lang, policy = policy_name)
+                    .to_string()
+            }
+            Self::ChosenDefaultCatchUp => {
+                t!("ChosenDefaultCatchUp", locale = lang).to_string()
+            }
+            Self::FailedSetCatchUp => {
+                t!("FailedSetCatchUp", locale = lang).to_string()
+            }
+            Self::SelectDst => t!("SelectDst", locale = lang).to_string(),
+            Self::ChosenDst(gap, overlap) => {
+                t!("ChosenDst", locale = lang, gap = gap, overlap = overlap)
+                    .to_string()
+            }
+            Self::FailedSetDst => t!("FailedSetDst", locale = lang).to_string(),
+            Self::LocationRequired => {
+                t!("LocationRequired", locale = lang).to_string()
+            }
+            Self::UpcomingTimes(times) => {
+                t!("UpcomingTimes", locale = lang, times = times).to_string()
+            }
+            Self::ReminderNotFound => {
+                t!("ReminderNotFound", locale = lang).to_string()
+            }
             Self::Help => t!("Help", locale = lang).to_string(),
         }
     }
@@ -145,6 +206,72 @@ impl TgResponse {
     }
 }
 
+/// Explain what is wrong with the reminder, quoting the offending word
+fn invalid_reminder_hint(text: &str, err: &ParseError, lang: &str) -> String {
+    let reason = match err.kind {
+        ParseErrorKind::Syntax { ref expected } if expected.is_empty() => {
+            t!("ParseErrorUnexpected", locale = lang).to_string()
+        }
+        ParseErrorKind::Syntax { ref expected } => {
+            let expected = expected
+                .iter()
+                .map(|token| match token {
+                    Expected::Date => t!("ExpectedDate", locale = lang),
+                    Expected::Time => t!("ExpectedTime", locale = lang),
+                    Expected::Weekday => t!("ExpectedWeekday", locale = lang),
+                    Expected::Interval => {
+                        t!("ExpectedInterval", locale = lang)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(&t!("ExpectedOr", locale = lang));
+            t!("ParseErrorSyntax", locale = lang, expected = expected)
+                .to_string()
+        }
+        ParseErrorKind::InvalidDate => {
+            t!("ParseErrorInvalidDate", locale = lang).to_string()
+        }
+        ParseErrorKind::ZeroInterval => {
+            t!("ParseErrorZeroInterval", locale = lang).to_string()
+        }
+        ParseErrorKind::InvalidNumber => {
+            t!("ParseErrorInvalidNumber", locale = lang).to_string()
+        }
+        ParseErrorKind::ZeroCount => {
+            t!("ParseErrorZeroCount", locale = lang).to_string()
+        }
+        ParseErrorKind::FractionNotAllowed => {
+            t!("ParseErrorFractionNotAllowed", locale = lang).to_string()
+        }
+    };
+    // widen an empty span to the whole word it points at
+    let span = if err.span.is_empty() {
+        let start = text[..err.span.start]
+            .rfind(char::is_whitespace)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let end = text[err.span.start..]
+            .find(char::is_whitespace)
+            .map(|i| err.span.start + i)
+            .unwrap_or(text.len());
+        start..end
+    } else {
+        err.span.clone()
+    };
+    let fragment = text.get(span).unwrap_or_default();
+    if fragment.trim().is_empty() {
+        t!("InvalidReminderAtEnd", locale = lang, reason = reason).to_string()
+    } else {
+        t!(
+            "InvalidReminder",
+            locale = lang,
+            fragment = fragment,
+            reason = reason
+        )
+        .to_string()
+    }
+}
+
 #[cfg(test)]
 impl Display for TgResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -218,6 +345,30 @@ pub(crate) async fn send_markup(
         .map(|_| ())
 }
 
+/// Silently reply to the message with a markup
+pub(crate) async fn send_silent_reply_markup(
+    text: &str,
+    markup: InlineKeyboardMarkup,
+    bot: &Bot,
+    chat_id: ChatId,
+    reply_to: MessageId,
+) -> Result<Message, RequestError> {
+    bot.send_message(chat_id, text)
+        .parse_mode(MarkdownV2)
+        .link_preview_options(LinkPreviewOptions {
+            is_disabled: true,
+            url: Default::default(),
+            prefer_small_media: Default::default(),
+            prefer_large_media: Default::default(),
+            show_above_text: Default::default(),
+        })
+        .disable_notification(true)
+        .reply_parameters(ReplyParameters::new(reply_to))
+        .reply_markup(markup)
+        .send()
+        .await
+}
+
 pub(crate) async fn edit_markup(
     markup: InlineKeyboardMarkup,
     bot: &Bot,
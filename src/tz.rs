@@ -3,8 +3,11 @@ use crate::db::Database;
 #[cfg(test)]
 use crate::db::MockDatabase as Database;
 use crate::err;
+use crate::holidays::get_user_holidays;
+use crate::sun::get_user_location;
 
 use chrono_tz::Tz;
+use remindee_pattern::{Calendar, DstGap, DstOverlap, DstPolicy};
 use teloxide::types::UserId;
 use tzf_rs::DefaultFinder;
 
@@ -385,3 +388,29 @@ pub(crate) fn get_timezone_name_of_location(
 ) -> &'static str {
     FINDER.get_tz_name(lng, lat)
 }
+
+pub(crate) async fn get_user_dst_policy(
+    db: &Database,
+    user_id: UserId,
+) -> DstPolicy {
+    db.get_user_dst_policy(user_id.0 as i64)
+        .await
+        .ok()
+        .flatten()
+        .map(|(gap, overlap)| DstPolicy {
+            gap: DstGap::from_code(&gap).unwrap_or_default(),
+            overlap: DstOverlap::from_code(&overlap).unwrap_or_default(),
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) async fn get_user_calendar(
+    db: &Database,
+    user_id: UserId,
+) -> Calendar {
+    Calendar {
+        holidays: get_user_holidays(db, user_id).await,
+        dst: get_user_dst_policy(db, user_id).await,
+        location: get_user_location(db, user_id).await,
+    }
+}